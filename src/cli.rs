@@ -1,8 +1,18 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+
+use crate::export::ExportFormat;
 
 #[derive(Parser, Debug)]
-#[command(name = "termquiz", version, about = "Terminal-based quiz application")]
+#[command(
+    name = "termquiz",
+    version,
+    about = "Terminal-based quiz application",
+    args_conflicts_with_subcommands = true
+)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Path to repo/file, or git URL [default: .]
     #[arg(default_value = ".")]
     pub path_or_url: String,
@@ -15,11 +25,104 @@ pub struct Cli {
     #[arg(long)]
     pub status: bool,
 
+    /// With --status, print JSON instead
+    #[arg(long, requires = "status")]
+    pub json: bool,
+
     /// Export current answers to file (for backup)
     #[arg(long, value_name = "path")]
     pub export: Option<String>,
 
+    /// Format for --export
+    #[arg(
+        long,
+        value_name = "format",
+        value_enum,
+        default_value = "yaml",
+        requires = "export"
+    )]
+    pub export_format: ExportFormat,
+
+    /// Merge answers from an --export backup into the current state
+    #[arg(long, value_name = "path")]
+    pub import: Option<String>,
+
+    /// SSH key to sign the submission with [default: $TERMQUIZ_SIGNING_KEY]
+    #[arg(long, value_name = "key")]
+    pub sign_key: Option<String>,
+
+    /// Instructor keys trusted to sign the quiz, in OpenSSH allowed_signers
+    /// format [default: $TERMQUIZ_TRUSTED_KEYS]
+    #[arg(long, value_name = "file")]
+    pub trust: Option<String>,
+
+    /// Rehearse at a simulated time: RFC 3339, or start/end with an offset
+    /// such as end-2m. Nothing is committed or pushed
+    #[arg(long, value_name = "time")]
+    pub simulate_time: Option<String>,
+
+    /// Rehearse with the clock running this many times faster, up to
+    /// 100000; 0 holds it still
+    #[arg(long, value_name = "factor")]
+    pub speed: Option<f64>,
+
     /// Directory for auto-clone [default: ~/termquiz-exams/<repo-name>]
     #[arg(long, value_name = "dir")]
     pub clone_to: Option<String>,
 }
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Rebuild answers from the journal as they stood at a point in time
+    Replay {
+        /// Path to repo/file [default: .]
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// RFC 3339 timestamp to stop at [default: latest]
+        #[arg(long, value_name = "time")]
+        at: Option<String>,
+
+        /// Print the journal entries instead of the reconstructed answers
+        #[arg(long)]
+        log: bool,
+    },
+    /// Check a submitted response/ against its manifest and signatures
+    Verify {
+        /// Path to a collected repo or its response/ directory [default: .]
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Public keys allowed to sign, in OpenSSH allowed_signers format
+        #[arg(long, value_name = "file")]
+        roster: Option<String>,
+
+        /// Instructor keys that must have signed the quiz, in OpenSSH
+        /// allowed_signers format [default: $TERMQUIZ_TRUSTED_KEYS]
+        #[arg(long, value_name = "file")]
+        trust: Option<String>,
+    },
+    /// Sign a quiz file (with its includes) for students to verify
+    SignQuiz {
+        /// Quiz markdown file
+        quiz: String,
+
+        /// Instructor's SSH key
+        #[arg(long, value_name = "key")]
+        key: String,
+    },
+    /// Decrypt a submission encrypted to the quiz's recipient key
+    Decrypt {
+        /// Path to a collected repo or its response/ directory [default: .]
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Private key: an age identity file or an unencrypted OpenSSH key
+        #[arg(long, value_name = "file")]
+        identity: String,
+
+        /// Where to unpack the answers [default: response-decrypted/ next to response/]
+        #[arg(long, value_name = "dir")]
+        out: Option<String>,
+    },
+}
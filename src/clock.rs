@@ -0,0 +1,188 @@
+use std::time::Instant;
+
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where every time decision gets "now": the window check, the timer and
+/// the timestamps written into the submission. Normally the system clock;
+/// `--simulate-time` and `--speed` swap in a simulated one for rehearsals.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Clock {
+    simulation: Option<Simulation>,
+}
+
+/// The fastest `--speed` a rehearsal accepts; even a week-long window
+/// passes in seconds.
+pub const MAX_SPEED: f64 = 100_000.0;
+
+#[derive(Debug, Clone, Copy)]
+struct Simulation {
+    start: DateTime<Utc>,
+    anchor: Instant,
+    speed: f64,
+}
+
+impl Clock {
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// A clock that reads `start` now and runs `speed` times real time; a
+    /// speed of 0 stops it.
+    pub fn simulated(start: DateTime<Utc>, speed: f64) -> Self {
+        Self {
+            simulation: Some(Simulation {
+                start,
+                anchor: Instant::now(),
+                speed,
+            }),
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match &self.simulation {
+            None => Utc::now(),
+            Some(sim) => {
+                let real = sim.anchor.elapsed().as_secs_f64();
+                // Saturate rather than panic if a long run passes the end of time
+                let elapsed = TimeDelta::try_milliseconds((real * sim.speed * 1000.0) as i64)
+                    .unwrap_or(TimeDelta::MAX);
+                sim.start
+                    .checked_add_signed(elapsed)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC)
+            }
+        }
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        self.simulation.is_some()
+    }
+}
+
+/// Parse `--simulate-time`: an RFC 3339 time, or `start`/`end` with an
+/// optional offset such as `end-2m30s` or `start+1h`.
+pub fn parse_simulated_time(
+    spec: &str,
+    start: DateTime<FixedOffset>,
+    end: DateTime<FixedOffset>,
+) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(spec) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    let invalid = || {
+        format!(
+            "Invalid --simulate-time '{}': expected an RFC 3339 time, or start/end with an offset like end-2m",
+            spec
+        )
+    };
+    let (base, rest) = if let Some(rest) = spec.strip_prefix("start") {
+        (start, rest)
+    } else if let Some(rest) = spec.strip_prefix("end") {
+        (end, rest)
+    } else {
+        return Err(invalid());
+    };
+    let base = base.with_timezone(&Utc);
+    let (sign, offset) = match rest.chars().next() {
+        None => return Ok(base),
+        Some('+') => (1, &rest[1..]),
+        Some('-') => (-1, &rest[1..]),
+        Some(_) => return Err(invalid()),
+    };
+    let secs = parse_duration(offset).ok_or_else(invalid)?;
+    TimeDelta::try_seconds(sign * secs)
+        .and_then(|offset| base.checked_add_signed(offset))
+        .ok_or_else(invalid)
+}
+
+/// `1h30m`, `90s`, `2m` and so on, in seconds.
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let mut total: i64 = 0;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().ok()?;
+        digits.clear();
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total = n
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))?;
+    }
+    (digits.is_empty() && !s.is_empty()).then_some(total)
+}
+
+/// Wall-clock movement beyond monotonic time that counts as a jump rather
+/// than NTP slew or a late tick.
+pub const JUMP_THRESHOLD_SECS: i64 = 5;
+/// Commit times come from another machine's clock, so allow some skew.
+pub const REMOTE_TOLERANCE_SECS: i64 = 300;
+
+/// A discontinuity in the local clock, recorded in the submission so
+/// instructors can spot a clock set back for more time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockEvent {
+    /// Local wall-clock time when it was noticed.
+    pub at: String,
+    /// `session`: the wall clock jumped while termquiz was running.
+    /// `restart`: the clock is behind the last save from a previous run.
+    /// `remote`: the clock is behind the quiz repo's latest commit.
+    pub source: String,
+    /// How far the wall clock is off, in seconds; negative means it was set
+    /// back. A forward `session` jump can also be a suspended laptop.
+    pub offset_seconds: i64,
+}
+
+impl ClockEvent {
+    fn new(source: &str, now: DateTime<Utc>, offset_seconds: i64) -> Self {
+        Self {
+            at: now.to_rfc3339(),
+            source: source.to_string(),
+            offset_seconds,
+        }
+    }
+}
+
+/// Compares wall-clock progress against `Instant`, which the user cannot
+/// set, between successive checks.
+pub struct ClockMonitor {
+    wall: DateTime<Utc>,
+    mono: Instant,
+}
+
+impl ClockMonitor {
+    pub fn new(wall: DateTime<Utc>, mono: Instant) -> Self {
+        Self { wall, mono }
+    }
+
+    /// Returns a `session` event if the wall clock moved more than
+    /// [`JUMP_THRESHOLD_SECS`] apart from monotonic time since the last check.
+    pub fn check(&mut self, wall: DateTime<Utc>, mono: Instant) -> Option<ClockEvent> {
+        let wall_ms = (wall - self.wall).num_milliseconds();
+        let mono_ms = mono.duration_since(self.mono).as_millis() as i64;
+        self.wall = wall;
+        self.mono = mono;
+
+        let skew = (wall_ms - mono_ms) / 1000;
+        (skew.abs() >= JUMP_THRESHOLD_SECS).then(|| ClockEvent::new("session", wall, skew))
+    }
+}
+
+/// A `restart` event if `now` is earlier than a save made in a previous run.
+pub fn check_restart(last_saved: DateTime<FixedOffset>, now: DateTime<Utc>) -> Option<ClockEvent> {
+    let behind = (now - last_saved.with_timezone(&Utc)).num_seconds();
+    (behind <= -JUMP_THRESHOLD_SECS).then(|| ClockEvent::new("restart", now, behind))
+}
+
+/// A `remote` event if `now` is well before a commit fetched from the remote.
+pub fn check_remote(commit_time: DateTime<FixedOffset>, now: DateTime<Utc>) -> Option<ClockEvent> {
+    let behind = (now - commit_time.with_timezone(&Utc)).num_seconds();
+    (behind <= -REMOTE_TOLERANCE_SECS).then(|| ClockEvent::new("remote", now, behind))
+}
@@ -0,0 +1,99 @@
+use std::fs;
+use std::io::{BufReader, Write};
+use std::path::Path;
+
+/// The whole response tree, tarred and age-encrypted to the quiz's
+/// `recipient`. It replaces everything else in `response/` except the
+/// manifest (and signature) covering it.
+pub const ARCHIVE_FILE: &str = "response.tar.age";
+
+/// Parse a frontmatter `recipient`: an age public key (`age1...`) or an
+/// OpenSSH public key line (`ssh-ed25519 AAAA...`, `ssh-rsa AAAA...`).
+pub fn parse_recipient(recipient: &str) -> Result<Box<dyn age::Recipient + Send>, String> {
+    let recipient = recipient.trim();
+    if recipient.starts_with("age1") {
+        recipient
+            .parse::<age::x25519::Recipient>()
+            .map(|r| Box::new(r) as Box<dyn age::Recipient + Send>)
+            .map_err(|e| format!("Invalid recipient: {}", e))
+    } else {
+        recipient
+            .parse::<age::ssh::Recipient>()
+            .map(|r| Box::new(r) as Box<dyn age::Recipient + Send>)
+            .map_err(|e| format!("Invalid recipient: {:?}", e))
+    }
+}
+
+/// Tar everything under `src_dir` and encrypt it to `recipient`, writing
+/// `out_file`.
+pub fn encrypt_dir(src_dir: &Path, recipient: &str, out_file: &Path) -> Result<(), String> {
+    let recipient = parse_recipient(recipient)?;
+    let encryptor =
+        age::Encryptor::with_recipients(std::iter::once(recipient.as_ref() as &dyn age::Recipient))
+            .map_err(|e| format!("Cannot encrypt submission: {}", e))?;
+
+    let file = fs::File::create(out_file)
+        .map_err(|e| format!("Cannot write {}: {}", out_file.display(), e))?;
+    let output = encryptor
+        .wrap_output(file)
+        .map_err(|e| format!("Cannot write {}: {}", out_file.display(), e))?;
+    let mut tar = tar::Builder::new(output);
+    tar.append_dir_all(".", src_dir)
+        .map_err(|e| format!("Cannot archive {}: {}", src_dir.display(), e))?;
+    let output = tar
+        .into_inner()
+        .map_err(|e| format!("Cannot archive {}: {}", src_dir.display(), e))?;
+    let mut file = output
+        .finish()
+        .map_err(|e| format!("Cannot write {}: {}", out_file.display(), e))?;
+    file.flush()
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Cannot write {}: {}", out_file.display(), e))
+}
+
+/// Decrypt `archive` with the private key in `identity` (an age identity
+/// file or an unencrypted OpenSSH private key) and unpack it into `out_dir`.
+pub fn decrypt_archive(archive: &Path, identity: &Path, out_dir: &Path) -> Result<(), String> {
+    let identities = read_identities(identity)?;
+    let file =
+        fs::File::open(archive).map_err(|e| format!("Cannot read {}: {}", archive.display(), e))?;
+    let decryptor = age::Decryptor::new_buffered(BufReader::new(file)).map_err(|e| {
+        format!(
+            "{} is not an encrypted submission: {}",
+            archive.display(),
+            e
+        )
+    })?;
+    let reader = decryptor
+        .decrypt(identities.iter().map(|i| i.as_ref() as &dyn age::Identity))
+        .map_err(|e| format!("Cannot decrypt {}: {}", archive.display(), e))?;
+
+    fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Cannot create {}: {}", out_dir.display(), e))?;
+    tar::Archive::new(reader)
+        .unpack(out_dir)
+        .map_err(|e| format!("Cannot unpack {}: {}", archive.display(), e))
+}
+
+fn read_identities(path: &Path) -> Result<Vec<Box<dyn age::Identity>>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if content.trim_start().starts_with("-----BEGIN") {
+        let identity =
+            age::ssh::Identity::from_buffer(content.as_bytes(), Some(path.display().to_string()))
+                .map_err(|e| format!("Invalid SSH key {}: {}", path.display(), e))?;
+        if !matches!(identity, age::ssh::Identity::Unencrypted(_)) {
+            return Err(format!(
+                "{} is passphrase-protected or unsupported; decrypt a copy without a passphrase",
+                path.display()
+            ));
+        }
+        return Ok(vec![Box::new(identity)]);
+    }
+    age::IdentityFile::from_buffer(content.as_bytes())
+        .and_then(|f| {
+            f.into_identities()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+        })
+        .map_err(|e| format!("Invalid identity file {}: {}", path.display(), e))
+}
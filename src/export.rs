@@ -0,0 +1,193 @@
+use clap::ValueEnum;
+
+use crate::model::{points_label, BodyElement, Question, QuestionKind};
+use crate::schema::{AnswerValue, QuestionRecord};
+use crate::state::AppState;
+use crate::submit;
+use crate::timer;
+
+/// Output format for `--export`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// answers.yaml, readable by `--import`
+    #[default]
+    Yaml,
+    /// answers.yaml as JSON, for downstream tooling
+    Json,
+    /// One row per question
+    Csv,
+    /// Printable transcript of questions and answers
+    Md,
+}
+
+pub fn render(state: &AppState, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Yaml => submit::build_answers_yaml(state),
+        ExportFormat::Json => build_json(state),
+        ExportFormat::Csv => Ok(build_csv(state)),
+        ExportFormat::Md => Ok(build_markdown(state)),
+    }
+}
+
+/// Same document as answers.yaml, so `schema_version` covers both.
+fn build_json(state: &AppState) -> Result<String, String> {
+    let doc = submit::build_answers_doc(state);
+    let mut json = serde_json::to_string_pretty(&doc)
+        .map_err(|e| format!("Cannot serialize answers: {}", e))?;
+    json.push('\n');
+    Ok(json)
+}
+
+fn build_csv(state: &AppState) -> String {
+    let doc = submit::build_answers_doc(state);
+    let mut out = String::from("number,id,title,type,status,answer,done,flagged,hints_used\n");
+    for (q, record) in state.quiz.questions.iter().zip(&doc.questions) {
+        let fields = [
+            q.number.to_string(),
+            q.key(),
+            q.title.clone(),
+            record.qtype.clone(),
+            state.question_status(q.number).name().to_string(),
+            record.answer.as_ref().map(answer_text).unwrap_or_default(),
+            record.done.to_string(),
+            record.flagged.to_string(),
+            record.hints_used.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a CSV field if it needs it (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Text of an answer; list answers are joined with "; ".
+fn answer_text(value: &AnswerValue) -> String {
+    match value {
+        AnswerValue::Text(t) => t.clone(),
+        AnswerValue::List(items) => items.join("; "),
+    }
+}
+
+fn build_markdown(state: &AppState) -> String {
+    let doc = submit::build_answers_doc(state);
+    let mut out = format!("# {}\n\n", state.quiz.title);
+
+    out.push_str(&format!("- Quiz file: {}\n", doc.quiz.source));
+    if let Some(ack) = &state.ack_data {
+        out.push_str(&format!("- Student: {}\n", ack.name));
+    }
+    if let Some(total) = doc.quiz.total_points {
+        out.push_str(&format!("- Total: {}\n", points_label(total)));
+    }
+    if let Some(started) = &state.started_at {
+        out.push_str(&format!("- Started: {}\n", started));
+    }
+    match &state.submitted_at {
+        Some(at) => out.push_str(&format!("- Submitted: {} ({})\n", at, doc.quiz.duration)),
+        None => out.push_str("- Submitted: not yet\n"),
+    }
+    out.push_str(&format!(
+        "- Exported: {}\n\n",
+        chrono::Local::now().to_rfc3339()
+    ));
+
+    for (q, record) in state.quiz.questions.iter().zip(&doc.questions) {
+        out.push_str("---\n\n");
+        push_question(&mut out, q, record);
+    }
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    out
+}
+
+fn push_question(out: &mut String, q: &Question, record: &QuestionRecord) {
+    match q.points {
+        Some(points) => out.push_str(&format!(
+            "## {}. {} ({})\n\n",
+            q.number,
+            q.title,
+            points_label(points)
+        )),
+        None => out.push_str(&format!("## {}. {}\n\n", q.number, q.title)),
+    }
+    for elem in &q.body_lines {
+        match elem {
+            BodyElement::Text(text) => out.push_str(&format!("{}\n\n", text)),
+            BodyElement::Code(block) => out.push_str(&format!(
+                "```{}\n{}\n```\n\n",
+                block.lang.as_deref().unwrap_or(""),
+                block.text.trim_end_matches('\n')
+            )),
+            BodyElement::Bold(text) => out.push_str(&format!("**{}**\n\n", text)),
+            BodyElement::Italic(text) => out.push_str(&format!("*{}*\n\n", text)),
+            BodyElement::InlineCode(text) => out.push_str(&format!("`{}`\n\n", text)),
+            BodyElement::ListItem(text) => out.push_str(&format!("- {}\n", text)),
+        }
+    }
+
+    match (&q.kind, &record.answer) {
+        (QuestionKind::SingleChoice(choices) | QuestionKind::MultiChoice(choices), answer) => {
+            let selected: Vec<&str> = match answer {
+                Some(AnswerValue::Text(label)) => vec![label.as_str()],
+                Some(AnswerValue::List(labels)) => labels.iter().map(|l| l.as_str()).collect(),
+                None => Vec::new(),
+            };
+            for c in choices {
+                let label = c.label.to_string();
+                let mark = if selected.contains(&label.as_str()) {
+                    "x"
+                } else {
+                    " "
+                };
+                out.push_str(&format!("- [{}] {}. {}\n", mark, label, c.text));
+            }
+            out.push('\n');
+            if selected.is_empty() {
+                out.push_str("*No answer.*\n\n");
+            }
+        }
+        (_, Some(AnswerValue::Text(text))) => {
+            out.push_str("**Answer:**\n\n");
+            for line in text.lines() {
+                out.push_str(&format!("> {}\n", line).replace("> \n", ">\n"));
+            }
+            out.push('\n');
+        }
+        (_, Some(AnswerValue::List(files))) => {
+            out.push_str("**Attached files:**\n\n");
+            for f in files {
+                out.push_str(&format!("- `{}`\n", f));
+            }
+            out.push('\n');
+        }
+        (_, None) => out.push_str("*No answer.*\n\n"),
+    }
+
+    let mut marks = Vec::new();
+    if record.done {
+        marks.push("done".to_string());
+    }
+    if record.flagged {
+        marks.push("flagged for review".to_string());
+    }
+    if record.hints_used > 0 {
+        marks.push(format!("hints used: {}", record.hints_used));
+    }
+    if record.time_spent_seconds > 0 {
+        let spent = timer::format_wait_duration(record.time_spent_seconds as i64);
+        marks.push(format!("time spent: {}", spent));
+    }
+    if !marks.is_empty() {
+        out.push_str(&format!("_{}_\n\n", marks.join(" · ")));
+    }
+}
@@ -53,11 +53,88 @@ pub fn git_add(repo: &Path, paths: &[&str]) -> Result<(), String> {
     Ok(())
 }
 
-pub fn git_commit(repo: &Path, message: &str) -> Result<(), String> {
-    run_git(&["commit", "-m", message], repo)?;
+/// Commit, SSH-signed with `signing_key` when one is configured.
+pub fn git_commit(repo: &Path, message: &str, signing_key: Option<&Path>) -> Result<(), String> {
+    match signing_key {
+        Some(key) => {
+            let key_config = format!("user.signingkey={}", key.display());
+            run_git(
+                &[
+                    "-c",
+                    "gpg.format=ssh",
+                    "-c",
+                    &key_config,
+                    "commit",
+                    "-S",
+                    "-m",
+                    message,
+                ],
+                repo,
+            )?;
+        }
+        None => {
+            run_git(&["commit", "-m", message], repo)?;
+        }
+    }
     Ok(())
 }
 
+/// The most recent commit that touched the submission manifest.
+pub fn submission_commit(repo: &Path) -> Option<String> {
+    run_git(
+        &[
+            "log",
+            "-n",
+            "1",
+            "--format=%H",
+            "--",
+            "response/manifest.yaml",
+        ],
+        repo,
+    )
+    .ok()
+    .map(|out| out.trim().to_string())
+    .filter(|hash| !hash.is_empty())
+}
+
+/// Check a commit's SSH signature against an allowed_signers roster and
+/// return the principal that signed it.
+pub fn verify_commit(repo: &Path, commit: &str, roster: &Path) -> Result<String, String> {
+    let roster = roster
+        .canonicalize()
+        .map_err(|e| format!("Cannot read roster {}: {}", roster.display(), e))?;
+    let config = format!("gpg.ssh.allowedSignersFile={}", roster.display());
+    let output = Command::new("git")
+        .args(["-c", &config, "verify-commit", commit])
+        .current_dir(repo)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // git reports e.g. `Good "git" signature for alice with ED25519 key ...`
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        return Err(format!(
+            "Commit {} is not signed by a key in the roster: {}",
+            &commit[..commit.len().min(12)],
+            stderr.trim()
+        ));
+    }
+    stderr
+        .split("signature for ")
+        .nth(1)
+        .and_then(|rest| rest.split(" with ").next())
+        .map(|p| p.trim().to_string())
+        .ok_or_else(|| format!("Cannot read signer from git: {}", stderr.trim()))
+}
+
+/// Commit time of HEAD, e.g. the instructor's latest commit right after
+/// a clone or pull.
+pub fn head_commit_time(repo: &Path) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    run_git(&["log", "-n", "1", "--format=%cI", "HEAD"], repo)
+        .ok()
+        .and_then(|out| chrono::DateTime::parse_from_rfc3339(out.trim()).ok())
+}
+
 pub fn git_push(repo: &Path) -> Result<(), String> {
     let output = Command::new("git")
         .args(["push"])
@@ -77,14 +154,29 @@ pub fn git_push(repo: &Path) -> Result<(), String> {
     }
 }
 
+/// Files that mark a submission: answers.yaml, or just the manifest when
+/// the answers are encrypted.
+const SUBMISSION_PATHS: [&str; 2] = ["response/answers.yaml", "response/manifest.yaml"];
+
 pub fn has_response_in_history(repo: &Path) -> bool {
-    run_git(&["log", "--all", "--format=%H", "--", "response/answers.yaml"], repo)
+    let mut args = vec!["log", "--all", "--format=%H", "--"];
+    args.extend(SUBMISSION_PATHS);
+    run_git(&args, repo)
+        .map(|out| !out.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Whether the upstream branch already has the submission, i.e. it was pushed.
+pub fn is_response_pushed(repo: &Path) -> bool {
+    let mut args = vec!["log", "@{upstream}", "--format=%H", "--"];
+    args.extend(SUBMISSION_PATHS);
+    run_git(&args, repo)
         .map(|out| !out.trim().is_empty())
         .unwrap_or(false)
 }
 
 pub fn has_response_in_worktree(repo: &Path) -> bool {
-    repo.join("response").join("answers.yaml").exists()
+    SUBMISSION_PATHS.iter().any(|p| repo.join(p).exists())
 }
 
 pub fn has_existing_submission(repo: &Path) -> bool {
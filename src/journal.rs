@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::SectionProgress;
+use crate::schema::AnswersDoc;
+use crate::state::AppState;
+use crate::submit;
+
+/// Take a snapshot (rewrite answers.yaml) after this many journal entries.
+pub const SNAPSHOT_EVERY: usize = 50;
+
+/// One change to the saved answer state, with the field's value before and
+/// after, so each entry reads on its own even right after a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub at: String,
+    /// Question key, or None for session-level fields such as `started_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+type FieldKey = (Option<String>, String);
+
+/// Journal bookkeeping kept in the app state: the last sequence number and
+/// the values last written, so each check only appends what changed.
+#[derive(Debug, Clone, Default)]
+pub struct Journal {
+    pub seq: u64,
+    pub since_snapshot: usize,
+    last: BTreeMap<FieldKey, Value>,
+}
+
+/// The live journal: entries since the last snapshot.
+pub fn journal_path(state_dir: &Path) -> PathBuf {
+    state_dir.join("journal.jsonl")
+}
+
+/// A rotated segment holding entries up to and including `seq`.
+fn segment_path(state_dir: &Path, seq: u64) -> PathBuf {
+    state_dir.join(format!("journal-{}.jsonl", seq))
+}
+
+/// Rotated segments, oldest first.
+fn segments(state_dir: &Path) -> Vec<PathBuf> {
+    let Ok(dir) = fs::read_dir(state_dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(u64, PathBuf)> = dir
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let seq = name.strip_prefix("journal-")?.strip_suffix(".jsonl")?;
+            Some((seq.parse().ok()?, entry.path()))
+        })
+        .collect();
+    found.sort();
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// The values the journal tracks, flattened out of an answers document.
+fn tracked_values(doc: &AnswersDoc) -> BTreeMap<FieldKey, Value> {
+    let mut values = BTreeMap::new();
+    let mut put = |question: Option<&str>, field: &str, value: Value| {
+        values.insert((question.map(|q| q.to_string()), field.to_string()), value);
+    };
+
+    put(None, "started_at", to_json(&doc.session.started_at));
+    put(None, "acknowledgment", to_json(&doc.session.acknowledgment));
+    put(None, "submitted_at", to_json(&doc.quiz.submitted_at));
+    put(None, "clock_events", to_json(&doc.clock_events));
+    put(None, "sections", to_json(&doc.sections));
+
+    for record in &doc.questions {
+        let key = record.id.as_deref();
+        put(key, "answer", to_json(&record.answer));
+        put(key, "done", Value::Bool(record.done));
+        put(key, "flagged", Value::Bool(record.flagged));
+        put(key, "hints_used", Value::from(record.hints_used));
+        put(key, "hint_reveals", to_json(&record.hint_reveals));
+    }
+    values
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Treat the current state as already journaled, e.g. right after loading.
+pub fn mark_synced(state: &mut AppState) {
+    let doc = submit::build_answers_doc(state);
+    state.journal.sync_to(&doc);
+}
+
+impl Journal {
+    pub fn sync_to(&mut self, doc: &AnswersDoc) {
+        self.last = tracked_values(doc);
+    }
+
+    /// Append an entry for every tracked value in `doc` that changed since
+    /// the last call, and fsync. Returns how many entries were written.
+    pub fn record(&mut self, state_dir: &Path, doc: &AnswersDoc) -> Result<usize, String> {
+        if self.last.is_empty() {
+            self.last = tracked_values(&cleared(doc));
+        }
+        let current = tracked_values(doc);
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let mut lines = String::new();
+        let mut seq = self.seq;
+        for (key, new) in &current {
+            let old = self.last.get(key).cloned().unwrap_or(Value::Null);
+            if old == *new {
+                continue;
+            }
+            seq += 1;
+            let entry = JournalEntry {
+                seq,
+                at: now.clone(),
+                question: key.0.clone(),
+                field: key.1.clone(),
+                old,
+                new: new.clone(),
+            };
+            let line = serde_json::to_string(&entry)
+                .map_err(|e| format!("Cannot encode journal entry: {}", e))?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+
+        let written = (seq - self.seq) as usize;
+        if written == 0 {
+            return Ok(0);
+        }
+
+        fs::create_dir_all(state_dir).map_err(|e| format!("Cannot create state dir: {}", e))?;
+        let path = journal_path(state_dir);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+        file.write_all(lines.as_bytes())
+            .map_err(|e| format!("Cannot write journal: {}", e))?;
+        file.sync_data()
+            .map_err(|e| format!("Cannot sync journal: {}", e))?;
+
+        self.seq = seq;
+        self.since_snapshot += written;
+        self.last = current;
+        Ok(written)
+    }
+}
+
+/// `doc` with everything the student did removed: the base that the whole
+/// journal applies to.
+pub fn cleared(doc: &AnswersDoc) -> AnswersDoc {
+    let mut doc = doc.clone();
+    doc.quiz.submitted_at = "unknown".to_string();
+    doc.quiz.acknowledged = false;
+    doc.session.started_at = None;
+    doc.session.acknowledgment = None;
+    doc.session.journal_seq = None;
+    doc.clock_events.clear();
+    for record in &mut doc.sections {
+        record.progress = SectionProgress::default();
+    }
+    for record in &mut doc.questions {
+        record.answer = None;
+        record.done = false;
+        record.flagged = false;
+        record.hint_used = false;
+        record.hints_used = 0;
+        record.hint_penalty = None;
+        record.hint_reveals.clear();
+    }
+    doc
+}
+
+/// Move the live journal aside once a snapshot with sequence number `seq`
+/// is on disk, so startup only replays what came after it. Nothing moves if
+/// the journal holds entries the snapshot does not include.
+pub fn rotate(state_dir: &Path, seq: u64) -> Result<(), String> {
+    let last = match read_tail(state_dir)?.last() {
+        Some(last) if last.seq <= seq => last.seq,
+        _ => return Ok(()),
+    };
+    fs::rename(journal_path(state_dir), segment_path(state_dir, last))
+        .map_err(|e| format!("Cannot rotate journal: {}", e))
+}
+
+/// When the last entry was written, i.e. the last save of any run.
+pub fn last_write_time(state_dir: &Path) -> Option<DateTime<FixedOffset>> {
+    let mut entries = read_tail(state_dir).ok()?;
+    if entries.is_empty() {
+        entries = read_file(segments(state_dir).last()?).ok()?;
+    }
+    DateTime::parse_from_rfc3339(&entries.last()?.at).ok()
+}
+
+/// Read the entries written since the last snapshot.
+pub fn read_tail(state_dir: &Path) -> Result<Vec<JournalEntry>, String> {
+    read_file(&journal_path(state_dir))
+}
+
+/// Read every entry, rotated segments included.
+pub fn read_entries(state_dir: &Path) -> Result<Vec<JournalEntry>, String> {
+    let mut entries = Vec::new();
+    for path in segments(state_dir) {
+        entries.extend(read_file(&path)?);
+    }
+    entries.extend(read_tail(state_dir)?);
+    Ok(entries)
+}
+
+/// A line torn by a crash mid-append is skipped.
+fn read_file(path: &Path) -> Result<Vec<JournalEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Apply one entry to a document. Entries for questions that no longer
+/// exist are ignored.
+pub fn apply(doc: &mut AnswersDoc, entry: &JournalEntry) {
+    let new = entry.new.clone();
+    match &entry.question {
+        None => match entry.field.as_str() {
+            "started_at" => doc.session.started_at = serde_json::from_value(new).unwrap_or(None),
+            "acknowledgment" => {
+                doc.session.acknowledgment = serde_json::from_value(new).unwrap_or(None)
+            }
+            "submitted_at" => {
+                if let Ok(at) = serde_json::from_value(new) {
+                    doc.quiz.submitted_at = at;
+                }
+            }
+            "clock_events" => doc.clock_events = serde_json::from_value(new).unwrap_or_default(),
+            "sections" => doc.sections = serde_json::from_value(new).unwrap_or_default(),
+            _ => {}
+        },
+        Some(key) => {
+            let Some(record) = doc
+                .questions
+                .iter_mut()
+                .find(|r| r.id.as_deref() == Some(key.as_str()))
+            else {
+                return;
+            };
+            match entry.field.as_str() {
+                "answer" => record.answer = serde_json::from_value(new).unwrap_or(None),
+                "done" => record.done = new.as_bool().unwrap_or(false),
+                "flagged" => record.flagged = new.as_bool().unwrap_or(false),
+                "hints_used" => {
+                    record.hints_used = new.as_u64().unwrap_or(0) as usize;
+                    record.hint_used = record.hints_used > 0;
+                }
+                "hint_reveals" => {
+                    record.hint_reveals = serde_json::from_value(new).unwrap_or_default()
+                }
+                _ => {}
+            }
+        }
+    }
+    doc.session.journal_seq = Some(entry.seq);
+}
+
+/// Apply entries after `after_seq` (and up to `until`, if given) to `doc`.
+/// Returns how many were applied.
+pub fn replay(
+    doc: &mut AnswersDoc,
+    entries: &[JournalEntry],
+    after_seq: u64,
+    until: Option<DateTime<FixedOffset>>,
+) -> usize {
+    let mut applied = 0;
+    for entry in entries.iter().filter(|e| e.seq > after_seq) {
+        if let Some(until) = until {
+            let at = DateTime::parse_from_rfc3339(&entry.at);
+            if at.map_or(true, |at| at > until) {
+                break;
+            }
+        }
+        apply(doc, entry);
+        applied += 1;
+    }
+    applied
+}
+
+/// Answers as they stood at `until` (or at the latest entry), rebuilt from an
+/// empty quiz by replaying the whole journal.
+pub fn reconstruct(
+    state: &AppState,
+    until: Option<DateTime<FixedOffset>>,
+) -> Result<AnswersDoc, String> {
+    let entries = read_entries(&state.state_dir)?;
+    if entries.is_empty() {
+        return Err(format!("No journal found in {}", state.state_dir.display()));
+    }
+    let mut doc = cleared(&submit::build_answers_doc(state));
+    replay(&mut doc, &entries, 0, until);
+    Ok(doc)
+}
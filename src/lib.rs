@@ -1,12 +1,21 @@
 pub mod cli;
+pub mod clock;
 pub mod editor;
+pub mod encrypt;
+pub mod export;
 pub mod git;
+pub mod journal;
+pub mod manifest;
+pub mod math;
 pub mod model;
 pub mod parser;
 pub mod persist;
+pub mod schema;
+pub mod sign;
 pub mod source;
 pub mod state;
 pub mod submit;
 pub mod timer;
 pub mod tui;
 pub mod ui;
+pub mod writer;
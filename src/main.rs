@@ -1,20 +1,31 @@
 mod cli;
+mod clock;
 mod editor;
+mod encrypt;
+mod export;
 mod git;
+mod journal;
+mod manifest;
+mod math;
 mod model;
 mod parser;
 mod persist;
+mod schema;
+mod sign;
 mod source;
 mod state;
 mod submit;
 mod timer;
 mod tui;
 mod ui;
+mod writer;
+
+use std::path::Path;
 
 use clap::Parser;
 
-use crate::cli::Cli;
-use crate::persist::compute_file_hash;
+use crate::cli::{Cli, Command};
+use crate::persist::compute_str_hash;
 use crate::state::{AppState, Screen};
 
 fn main() {
@@ -26,19 +37,41 @@ fn main() {
 
 fn run() -> Result<(), String> {
     let cli = Cli::parse();
+    let path_or_url = match &cli.command {
+        Some(Command::Replay { path, .. }) => path,
+        Some(Command::Verify {
+            path,
+            roster,
+            trust,
+        }) => {
+            let trusted = sign::trusted_keys(trust.as_deref());
+            return verify(
+                Path::new(path),
+                roster.as_deref().map(Path::new),
+                trusted.as_deref(),
+            );
+        }
+        Some(Command::SignQuiz { quiz, key }) => return sign_quiz(Path::new(quiz), Path::new(key)),
+        Some(Command::Decrypt {
+            path,
+            identity,
+            out,
+        }) => {
+            return decrypt(
+                Path::new(path),
+                Path::new(identity),
+                out.as_deref().map(Path::new),
+            );
+        }
+        None => &cli.path_or_url,
+    };
 
     // Resolve source
-    let (repo_dir, quiz_path) = source::resolve_source(
-        &cli.path_or_url,
-        cli.clone_to.as_deref(),
-    )?;
+    let (repo_dir, quiz_path) = source::resolve_source(path_or_url, cli.clone_to.as_deref())?;
 
-    // Compute quiz file hash
-    let quiz_hash = compute_file_hash(&quiz_path)?;
-
-    // Read and parse quiz
-    let content = std::fs::read_to_string(&quiz_path)
-        .map_err(|e| format!("Cannot read quiz file: {}", e))?;
+    // Read quiz with includes resolved; the hash covers the full content
+    let content = source::read_quiz(&quiz_path)?;
+    let quiz_hash = compute_str_hash(&content);
 
     let quiz_filename = quiz_path
         .file_name()
@@ -48,82 +81,308 @@ fn run() -> Result<(), String> {
 
     let quiz = parser::parse_quiz(&content, &quiz_filename, &quiz_hash)?;
 
+    // Create state
+    let mut state = AppState::new(quiz, repo_dir.clone());
+
+    // Handle `termquiz replay`
+    if let Some(Command::Replay { at, log, .. }) = &cli.command {
+        return replay(&state, at.as_deref(), *log);
+    }
+
+    // The time window comes from the quiz file, so check the instructor's
+    // signature over it before anything else reads or writes answers
+    let trusted = sign::trusted_keys(cli.trust.as_deref());
+    state.quiz_signature = Some(sign::check_quiz(&quiz_path, &content, trusted.as_deref())?);
+
+    // Rehearsal: a simulated clock and a throwaway draft, so instructors can
+    // walk through the time window without touching real answers or the repo
+    if cli.simulate_time.is_some() || cli.speed.is_some() {
+        let fm = &state.quiz.frontmatter;
+        let start = match &cli.simulate_time {
+            Some(spec) => clock::parse_simulated_time(spec, fm.start, fm.end)?,
+            None => chrono::Utc::now(),
+        };
+        let speed = cli.speed.unwrap_or(1.0);
+        if !(0.0..=clock::MAX_SPEED).contains(&speed) {
+            return Err(format!(
+                "--speed must be between 0 and {}",
+                clock::MAX_SPEED
+            ));
+        }
+        state.clock = clock::Clock::simulated(start, speed);
+        state.state_dir = state.state_dir.join("rehearsal");
+        persist::clear_state(&state.state_dir)?;
+        eprintln!(
+            "Rehearsal from {} at {}x; nothing will be committed or pushed. Responses go to {}",
+            start.to_rfc3339(),
+            speed,
+            state.state_dir.join("submitted").display()
+        );
+    }
+
     // Handle --clear
-    if cli.clear {
-        persist::clear_state(&repo_dir)?;
+    if state.is_rehearsal() {
+        // Leave the real draft in the repo alone
+    } else if cli.clear {
+        persist::clear_state(&state.state_dir)?;
         eprintln!("State cleared.");
+    } else {
+        match persist::adopt_legacy_draft(&repo_dir, &state.state_dir) {
+            Ok(true) => eprintln!("Moved draft answers to {}", state.state_dir.display()),
+            Ok(false) => {}
+            Err(e) => eprintln!("Warning: {}", e),
+        }
     }
 
-    // Create state
-    let mut state = AppState::new(quiz, repo_dir.clone());
-
     // Load persisted state
     if !cli.clear {
         match persist::load_state(&mut state) {
-            Ok(true) => {
-                // State loaded successfully
-            }
-            Ok(false) => {
-                // No saved state
-            }
+            Ok(_) => {}
             Err(e) => {
                 eprintln!("Warning: {}", e);
             }
         }
     }
 
+    // Handle --import
+    if let Some(ref import_path) = cli.import {
+        let (doc, overwritten) = persist::read_import(&state, Path::new(import_path))?;
+        if !overwritten.is_empty() {
+            eprintln!("Importing {} will change:", import_path);
+            for line in &overwritten {
+                eprintln!("  {}", line);
+            }
+            if !confirm("Continue? [y/N] ")? {
+                return Err("Import cancelled".to_string());
+            }
+        }
+        persist::import_doc(&mut state, &doc)?;
+        eprintln!("Answers imported from {}", import_path);
+    }
+
+    if !state.migration_notes.is_empty() {
+        state.push_dialog(state::Dialog::QuizUpdated);
+    }
+
     // Handle --status
     if cli.status {
-        persist::print_status(&state);
+        // Status of a text question counts the live input, so load it
+        state.load_text_input_for_current();
+        if cli.json {
+            println!("{}", persist::status_json(&state)?);
+        } else {
+            persist::print_status(&state);
+        }
         return Ok(());
     }
 
     // Handle --export
     if let Some(ref export_path) = cli.export {
-        persist::export_answers(&state, export_path)?;
+        persist::export_answers(&state, export_path, cli.export_format)?;
         eprintln!("Answers exported to {}", export_path);
         return Ok(());
     }
 
+    // The timer trusts the wall clock, so note if it is behind a time it
+    // has already seen: the last save, or the instructor's latest commit. A
+    // rehearsal clock is meant to disagree with both
+    if !state.clock.is_simulated() {
+        let now = state.clock.now();
+        let behind_last_save = journal::last_write_time(&state.state_dir)
+            .and_then(|last| clock::check_restart(last, now));
+        let behind_remote =
+            git::head_commit_time(&repo_dir).and_then(|commit| clock::check_remote(commit, now));
+        state
+            .clock_events
+            .extend(behind_last_save.into_iter().chain(behind_remote));
+    }
+
+    // Signing happens inside the TUI, so make sure it will work first
+    state.signing_key = sign::signing_key(cli.sign_key.as_deref());
+    if let Some(ref key) = state.signing_key {
+        sign::check_key(key)?;
+    }
+
     // Check for existing submission
-    if git::is_git_repo(&repo_dir) && git::has_existing_submission(&repo_dir) {
+    if !state.is_rehearsal()
+        && git::is_git_repo(&repo_dir)
+        && git::has_existing_submission(&repo_dir)
+    {
         state.screen = Screen::AlreadySubmitted;
     } else {
-        // Determine initial screen based on time window
-        let now = chrono::Utc::now();
-        let start = state.quiz.frontmatter.start;
-        let end = state.quiz.frontmatter.end;
-
-        if now < start {
-            state.screen = Screen::Waiting;
-        } else if now > end {
-            state.screen = Screen::Closed;
-        } else {
-            // Within time window - show preamble
-            state.screen = Screen::Preamble;
-
-            // If we already have ack data or don't need ack, and already started
-            if state.started_at.is_some() {
-                let needs_ack = state
-                    .quiz
-                    .frontmatter
-                    .acknowledgment
-                    .as_ref()
-                    .map(|a| a.required)
-                    .unwrap_or(false);
-
-                if !needs_ack || state.ack_data.is_some() {
-                    state.screen = Screen::Working;
-                }
-            }
-        }
+        state.screen = state.initial_screen();
     }
 
     // Start timer
-    let timer_rx = timer::spawn_timer(state.quiz.frontmatter.end);
+    let timer_rx = timer::spawn_timer(
+        state.quiz.frontmatter.end,
+        state.quiz.frontmatter.grace,
+        state.clock,
+        state.quiz.frontmatter.warnings.clone(),
+    );
 
     // Run TUI
     tui::run_tui(state, timer_rx)?;
 
     Ok(())
 }
+
+fn verify(path: &Path, roster: Option<&Path>, trusted: Option<&Path>) -> Result<(), String> {
+    let (repo_dir, response_dir) = if path.join("response").is_dir() {
+        (path.to_path_buf(), path.join("response"))
+    } else {
+        (
+            path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            path.to_path_buf(),
+        )
+    };
+    let problems = manifest::verify(&response_dir)?;
+    if !problems.is_empty() {
+        for p in &problems {
+            println!("  {}", p);
+        }
+        return Err(format!(
+            "{} does not match its manifest",
+            response_dir.display()
+        ));
+    }
+    println!("{}: all files match the manifest", response_dir.display());
+    verify_quiz(&repo_dir, &response_dir, trusted)?;
+
+    let sig = response_dir.join(manifest::SIGNATURE_FILE);
+    let Some(roster) = roster else {
+        if sig.exists() {
+            println!("Manifest is signed; pass --roster to check the signature");
+        }
+        return Ok(());
+    };
+    if !sig.exists() {
+        return Err(format!("{} is not signed", response_dir.display()));
+    }
+    let signer = sign::verify_file(roster, &response_dir.join(manifest::MANIFEST_FILE), &sig)?;
+    println!("Manifest signed by {}", signer);
+
+    if git::is_git_repo(&repo_dir) {
+        let commit = git::submission_commit(&repo_dir)
+            .ok_or_else(|| "No commit contains response/manifest.yaml".to_string())?;
+        let committer = git::verify_commit(&repo_dir, &commit, roster)?;
+        if committer != signer {
+            return Err(format!(
+                "Submit commit is signed by {}, but the manifest by {}",
+                committer, signer
+            ));
+        }
+        println!("Submit commit {} signed by {}", &commit[..12], committer);
+    }
+    Ok(())
+}
+
+/// Check the quiz in a collected repo against the instructor's keys, and
+/// that the answers were given against that same quiz.
+fn verify_quiz(repo_dir: &Path, response_dir: &Path, trusted: Option<&Path>) -> Result<(), String> {
+    let (_, quiz_path) = source::resolve_source(&repo_dir.to_string_lossy(), None)?;
+    let content = source::read_quiz(&quiz_path)?;
+    let Some(trusted) = trusted else {
+        if sign::quiz_signature_path(&quiz_path).exists() {
+            println!("Quiz is signed; pass --trust to check the signature");
+        }
+        return Ok(());
+    };
+    let signature = sign::check_quiz(&quiz_path, &content, Some(trusted))?;
+    println!(
+        "Quiz signed by {}",
+        signature.trim_start_matches("verified: ")
+    );
+
+    let answers = response_dir.join("answers.yaml");
+    if answers.exists() {
+        let yaml = std::fs::read_to_string(&answers)
+            .map_err(|e| format!("Cannot read {}: {}", answers.display(), e))?;
+        let doc = schema::from_yaml(&yaml)?;
+        if doc.session.quiz_file_hash != compute_str_hash(&content) {
+            return Err(format!(
+                "{} was answered against a different version of {}",
+                answers.display(),
+                quiz_path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn sign_quiz(quiz_path: &Path, key: &Path) -> Result<(), String> {
+    // Sign exactly what students hash and parse: the content with includes
+    let content = source::read_quiz(quiz_path)?;
+    parser::parse_quiz(&content, &quiz_path.to_string_lossy(), "")?;
+    let sig = sign::sign_quiz(key, quiz_path, &content)?;
+    println!("Wrote {}", sig.display());
+    Ok(())
+}
+
+fn decrypt(path: &Path, identity: &Path, out: Option<&Path>) -> Result<(), String> {
+    let response_dir = if path.join("response").is_dir() {
+        path.join("response")
+    } else {
+        path.to_path_buf()
+    };
+    let archive = response_dir.join(encrypt::ARCHIVE_FILE);
+    if !archive.exists() {
+        return Err(format!(
+            "{} has no encrypted submission",
+            response_dir.display()
+        ));
+    }
+    let out_dir = match out {
+        Some(dir) => dir.to_path_buf(),
+        None => response_dir.with_file_name("response-decrypted"),
+    };
+    encrypt::decrypt_archive(&archive, identity, &out_dir)?;
+    println!("Decrypted into {}", out_dir.display());
+
+    // The unpacked tree carries its own manifest over the plaintext files
+    let problems = manifest::verify(&out_dir)?;
+    if !problems.is_empty() {
+        for p in &problems {
+            println!("  {}", p);
+        }
+        return Err(format!("{} does not match its manifest", out_dir.display()));
+    }
+    println!("{}: all files match the manifest", out_dir.display());
+    Ok(())
+}
+
+fn confirm(prompt: &str) -> Result<bool, String> {
+    eprint!("{}", prompt);
+    let mut reply = String::new();
+    std::io::stdin()
+        .read_line(&mut reply)
+        .map_err(|e| format!("Cannot read reply: {}", e))?;
+    Ok(matches!(reply.trim(), "y" | "Y" | "yes"))
+}
+
+fn replay(state: &AppState, at: Option<&str>, log: bool) -> Result<(), String> {
+    let until = at
+        .map(|t| {
+            chrono::DateTime::parse_from_rfc3339(t)
+                .map_err(|e| format!("Invalid --at time '{}': {}", t, e))
+        })
+        .transpose()?;
+
+    if log {
+        for entry in journal::read_entries(&state.state_dir)? {
+            if until.is_some_and(|u| {
+                chrono::DateTime::parse_from_rfc3339(&entry.at).map_or(true, |at| at > u)
+            }) {
+                break;
+            }
+            let line = serde_json::to_string(&entry)
+                .map_err(|e| format!("Cannot encode journal entry: {}", e))?;
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    let doc = journal::reconstruct(state, until)?;
+    print!("{}", schema::to_yaml(&doc)?);
+    Ok(())
+}
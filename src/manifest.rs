@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{compute_file_hash, compute_str_hash};
+use crate::state::AppState;
+
+pub const MANIFEST_FILE: &str = "manifest.yaml";
+/// Detached signature over the manifest, written when the student signs.
+pub const SIGNATURE_FILE: &str = "manifest.yaml.sig";
+
+/// Everything in `response/` at submit time, so graders can check that
+/// nothing changed after the submit commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub manifest_version: u32,
+    pub created_at: String,
+    pub files: Vec<ManifestEntry>,
+    /// sha256 over `"<sha256>  <path>\n"` for every entry, sorted by path.
+    pub tree_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Relative to `response/`, with `/` separators.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Question key, for attachments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attached_at: Option<String>,
+}
+
+/// Hash every file under `response_dir` and write `manifest.yaml` there.
+pub fn write_manifest(state: &AppState, response_dir: &Path) -> Result<Manifest, String> {
+    let mut files = Vec::new();
+    for path in list_files(response_dir)? {
+        let meta = fs::metadata(response_dir.join(&path))
+            .map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        let mut entry = ManifestEntry {
+            sha256: compute_file_hash(&response_dir.join(&path))?,
+            size: meta.len(),
+            question: None,
+            original_name: None,
+            attached_at: None,
+            path,
+        };
+        describe_attachment(state, &mut entry);
+        files.push(entry);
+    }
+
+    let manifest = Manifest {
+        manifest_version: 1,
+        created_at: state.clock.now().with_timezone(&Local).to_rfc3339(),
+        tree_hash: tree_hash(&files),
+        files,
+    };
+    let yaml = serde_yaml::to_string(&manifest)
+        .map_err(|e| format!("Cannot serialize manifest: {}", e))?;
+    fs::write(response_dir.join(MANIFEST_FILE), yaml)
+        .map_err(|e| format!("Cannot write {}: {}", MANIFEST_FILE, e))?;
+    Ok(manifest)
+}
+
+/// Fill in question and attach time for a `files/<key>/<name>` entry from
+/// the staged copy, whose mtime is when the student attached it.
+fn describe_attachment(state: &AppState, entry: &mut ManifestEntry) {
+    let mut parts = entry.path.splitn(3, '/');
+    let (Some("files"), Some(key), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+        return;
+    };
+    entry.question = Some(key.to_string());
+    entry.original_name = Some(name.to_string());
+
+    let staged = state
+        .quiz
+        .questions
+        .iter()
+        .find(|q| q.key() == key)
+        .and_then(|q| state.answers.get(&q.number))
+        .and_then(|a| a.files.as_ref())
+        .and_then(|files| {
+            files
+                .iter()
+                .map(PathBuf::from)
+                .find(|f| f.file_name().is_some_and(|n| n.to_string_lossy() == name))
+        });
+    entry.attached_at = staged
+        .and_then(|f| fs::metadata(f).ok())
+        .and_then(|m| m.modified().ok())
+        .map(|t| DateTime::<Local>::from(t).to_rfc3339());
+}
+
+fn tree_hash(files: &[ManifestEntry]) -> String {
+    let mut sorted: Vec<&ManifestEntry> = files.iter().collect();
+    sorted.sort_by(|a, b| a.path.cmp(&b.path));
+    let listing: String = sorted
+        .iter()
+        .map(|e| format!("{}  {}\n", e.sha256, e.path))
+        .collect();
+    compute_str_hash(&listing)
+}
+
+/// Relative paths of all files under `dir` except the manifest and its
+/// signature, sorted.
+fn list_files(dir: &Path) -> Result<Vec<String>, String> {
+    fn walk(dir: &Path, prefix: &str, out: &mut Vec<String>) -> Result<(), String> {
+        let entries =
+            fs::read_dir(dir).map_err(|e| format!("Cannot read {}: {}", dir.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Cannot read {}: {}", dir.display(), e))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let rel = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if entry.path().is_dir() {
+                walk(&entry.path(), &rel, out)?;
+            } else if rel != MANIFEST_FILE && rel != SIGNATURE_FILE {
+                out.push(rel);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir, "", &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// Check `response_dir` against its manifest. Returns one line per file
+/// that was added, removed or changed; empty means it matches.
+pub fn verify(response_dir: &Path) -> Result<Vec<String>, String> {
+    let path = response_dir.join(MANIFEST_FILE);
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let manifest: Manifest =
+        serde_yaml::from_str(&content).map_err(|e| format!("Corrupt {}: {}", MANIFEST_FILE, e))?;
+
+    let mut problems = Vec::new();
+    if tree_hash(&manifest.files) != manifest.tree_hash {
+        problems.push("tree_hash does not match the listed files".to_string());
+    }
+
+    let present = list_files(response_dir)?;
+    for entry in &manifest.files {
+        let file = response_dir.join(&entry.path);
+        if !present.contains(&entry.path) {
+            problems.push(format!("{}: missing", entry.path));
+        } else if compute_file_hash(&file)? != entry.sha256 {
+            problems.push(format!("{}: modified", entry.path));
+        }
+    }
+    for path in present {
+        if !manifest.files.iter().any(|e| e.path == path) {
+            problems.push(format!("{}: not in manifest", path));
+        }
+    }
+    Ok(problems)
+}
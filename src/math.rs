@@ -0,0 +1,593 @@
+/// Convert a LaTeX math snippet (the part between `$...$` or `$$...$$`) into
+/// plain Unicode, e.g. `\sum_{i=1}^n i` becomes `∑ᵢ₌₁ⁿ i`.
+///
+/// Only a practical subset is supported: Greek letters, common operators and
+/// relations, sub/superscripts, `\frac`, `\sqrt`, `\text` and `\mathbb`.
+/// Anything else is kept in a readable form (`x^(a+q)`, `\unknown`) rather
+/// than dropped.
+pub fn to_unicode(latex: &str) -> String {
+    let chars: Vec<char> = latex.chars().collect();
+    let mut pos = 0;
+    let out = convert(&chars, &mut pos, false);
+
+    // Collapse whitespace runs left over from the source and from spacing commands
+    let mut result = String::new();
+    for ch in out.chars() {
+        if ch.is_whitespace() {
+            if !result.is_empty() && !result.ends_with(' ') {
+                result.push(' ');
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    result.trim_end().to_string()
+}
+
+/// Convert until the end of input, or until the closing `}` of the current group.
+fn convert(chars: &[char], pos: &mut usize, in_group: bool) -> String {
+    let mut out = String::new();
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        match c {
+            '}' if in_group => {
+                *pos += 1;
+                return out;
+            }
+            '{' => {
+                *pos += 1;
+                out.push_str(&convert(chars, pos, true));
+            }
+            '\\' => {
+                *pos += 1;
+                out.push_str(&command(chars, pos));
+            }
+            '^' | '_' => {
+                *pos += 1;
+                let arg = argument(chars, pos);
+                out.push_str(&script(&arg, c == '^'));
+            }
+            '\'' => {
+                *pos += 1;
+                out.push('′');
+            }
+            _ => {
+                *pos += 1;
+                out.push(c);
+            }
+        }
+    }
+    out
+}
+
+/// Read one argument: a `{...}` group, a single command, or a single character.
+fn argument(chars: &[char], pos: &mut usize) -> String {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+    match chars.get(*pos) {
+        Some('{') => {
+            *pos += 1;
+            convert(chars, pos, true)
+        }
+        Some('\\') => {
+            *pos += 1;
+            command(chars, pos)
+        }
+        Some(&c) => {
+            *pos += 1;
+            c.to_string()
+        }
+        None => String::new(),
+    }
+}
+
+/// Read a `{...}` group verbatim, for `\text{...}` and friends.
+fn raw_group(chars: &[char], pos: &mut usize) -> String {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+    if chars.get(*pos) != Some(&'{') {
+        return argument(chars, pos);
+    }
+    *pos += 1;
+    let mut depth = 1;
+    let mut out = String::new();
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        *pos += 1;
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Read an optional `[...]` argument, as in `\sqrt[3]{x}`.
+fn optional_argument(chars: &[char], pos: &mut usize) -> Option<String> {
+    if chars.get(*pos) != Some(&'[') {
+        return None;
+    }
+    let close = chars[*pos..].iter().position(|&c| c == ']')?;
+    let inner: Vec<char> = chars[*pos + 1..*pos + close].to_vec();
+    *pos += close + 1;
+    let mut inner_pos = 0;
+    Some(convert(&inner, &mut inner_pos, false))
+}
+
+/// Handle a command; `pos` points just past the backslash.
+fn command(chars: &[char], pos: &mut usize) -> String {
+    let Some(&first) = chars.get(*pos) else {
+        return "\\".to_string();
+    };
+    if !first.is_ascii_alphabetic() {
+        *pos += 1;
+        return match first {
+            ',' | ':' | ';' | ' ' => " ".to_string(),
+            '!' => String::new(),
+            '\\' => " ".to_string(),
+            '|' => "‖".to_string(),
+            c => c.to_string(),
+        };
+    }
+
+    let start = *pos;
+    while *pos < chars.len() && chars[*pos].is_ascii_alphabetic() {
+        *pos += 1;
+    }
+    let name: String = chars[start..*pos].iter().collect();
+
+    match name.as_str() {
+        "frac" | "dfrac" | "tfrac" => {
+            let num = argument(chars, pos);
+            let den = argument(chars, pos);
+            fraction(&num, &den)
+        }
+        "sqrt" => {
+            let index = optional_argument(chars, pos);
+            let arg = argument(chars, pos);
+            let radical = match index.as_deref() {
+                None | Some("2") => "√".to_string(),
+                Some("3") => "∛".to_string(),
+                Some("4") => "∜".to_string(),
+                Some(n) => format!("{}√", script(n, true)),
+            };
+            if arg.chars().count() > 1 && !is_wrapped(&arg) {
+                format!("{}({})", radical, arg)
+            } else {
+                format!("{}{}", radical, arg)
+            }
+        }
+        "text" | "textrm" | "textit" | "textbf" | "mbox" => raw_group(chars, pos),
+        "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathtt" | "operatorname" | "boldsymbol" => {
+            argument(chars, pos)
+        }
+        "mathbb" => argument(chars, pos).chars().map(double_struck).collect(),
+        "hat" | "widehat" => accent(&argument(chars, pos), '\u{0302}'),
+        "bar" | "overline" => accent(&argument(chars, pos), '\u{0304}'),
+        "tilde" | "widetilde" => accent(&argument(chars, pos), '\u{0303}'),
+        "dot" => accent(&argument(chars, pos), '\u{0307}'),
+        "vec" => accent(&argument(chars, pos), '\u{20D7}'),
+        // Sizing and delimiter modifiers carry no content of their own
+        "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "displaystyle" | "limits" => {
+            String::new()
+        }
+        "quad" | "qquad" => " ".to_string(),
+        "mod" | "bmod" => " mod ".to_string(),
+        "pmod" => format!(" (mod {})", argument(chars, pos)),
+        "log" | "ln" | "lg" | "exp" | "sin" | "cos" | "tan" | "sec" | "csc" | "cot" | "max"
+        | "min" | "lim" | "sup" | "inf" | "det" | "gcd" | "deg" | "arg" | "dim" | "ker" | "Pr"
+        | "argmax" | "argmin" => name.clone(),
+        _ => match symbol(&name) {
+            Some(s) => s.to_string(),
+            // Unknown command: keep it and its arguments visible so the
+            // reader can still make sense of it
+            None => {
+                let mut out = format!("\\{}", name);
+                while chars.get(*pos) == Some(&'{') {
+                    *pos += 1;
+                    out.push('{');
+                    out.push_str(&convert(chars, pos, true));
+                    out.push('}');
+                }
+                out
+            }
+        },
+    }
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    let s = match name {
+        // Greek, lowercase
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "omicron" => "ο",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        // Greek, uppercase
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        // Big operators
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        // Binary operators
+        "times" => "×",
+        "cdot" => "·",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "•",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        // Relations
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "ll" => "≪",
+        "gg" => "≫",
+        "approx" => "≈",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "equiv" => "≡",
+        "propto" => "∝",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        // Arrows
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        // Logic and sets
+        "forall" => "∀",
+        "exists" => "∃",
+        "nexists" => "∄",
+        "neg" | "lnot" => "¬",
+        "emptyset" | "varnothing" => "∅",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "aleph" => "ℵ",
+        "ell" => "ℓ",
+        "hbar" => "ℏ",
+        "prime" => "′",
+        "angle" => "∠",
+        "degree" => "°",
+        "therefore" => "∴",
+        "because" => "∵",
+        // Dots
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        // Delimiters
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lvert" | "rvert" | "vert" => "|",
+        "lVert" | "rVert" | "Vert" => "‖",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Render `^arg` / `_arg` with Unicode script characters, or fall back to
+/// `^(...)` when some character has no script form.
+fn script(arg: &str, superscript: bool) -> String {
+    let mapped: Option<String> = arg
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            if superscript {
+                superscript_char(c)
+            } else {
+                subscript_char(c)
+            }
+        })
+        .collect();
+    match mapped {
+        Some(s) => s,
+        None => {
+            let marker = if superscript { '^' } else { '_' };
+            if arg.chars().count() == 1 {
+                format!("{}{}", marker, arg)
+            } else {
+                format!("{}({})", marker, arg)
+            }
+        }
+    }
+}
+
+fn superscript_char(c: char) -> Option<char> {
+    let s = match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'A' => 'ᴬ',
+        'B' => 'ᴮ',
+        'D' => 'ᴰ',
+        'E' => 'ᴱ',
+        'G' => 'ᴳ',
+        'H' => 'ᴴ',
+        'I' => 'ᴵ',
+        'J' => 'ᴶ',
+        'K' => 'ᴷ',
+        'L' => 'ᴸ',
+        'M' => 'ᴹ',
+        'N' => 'ᴺ',
+        'O' => 'ᴼ',
+        'P' => 'ᴾ',
+        'R' => 'ᴿ',
+        'T' => 'ᵀ',
+        'U' => 'ᵁ',
+        'V' => 'ⱽ',
+        'W' => 'ᵂ',
+        'α' => 'ᵅ',
+        'β' => 'ᵝ',
+        'γ' => 'ᵞ',
+        'δ' => 'ᵟ',
+        'θ' => 'ᶿ',
+        'ϕ' | 'φ' => 'ᵠ',
+        'χ' => 'ᵡ',
+        '′' => '′',
+        '∗' | '*' => '*',
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn subscript_char(c: char) -> Option<char> {
+    let s = match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        'β' => 'ᵦ',
+        'γ' => 'ᵧ',
+        'ρ' => 'ᵨ',
+        'ϕ' | 'φ' => 'ᵩ',
+        'χ' => 'ᵪ',
+        _ => return None,
+    };
+    Some(s)
+}
+
+fn fraction(num: &str, den: &str) -> String {
+    let vulgar = match (num.trim(), den.trim()) {
+        ("1", "2") => Some("½"),
+        ("1", "3") => Some("⅓"),
+        ("2", "3") => Some("⅔"),
+        ("1", "4") => Some("¼"),
+        ("3", "4") => Some("¾"),
+        ("1", "5") => Some("⅕"),
+        ("2", "5") => Some("⅖"),
+        ("3", "5") => Some("⅗"),
+        ("4", "5") => Some("⅘"),
+        ("1", "6") => Some("⅙"),
+        ("5", "6") => Some("⅚"),
+        ("1", "7") => Some("⅐"),
+        ("1", "8") => Some("⅛"),
+        ("3", "8") => Some("⅜"),
+        ("5", "8") => Some("⅝"),
+        ("7", "8") => Some("⅞"),
+        ("1", "9") => Some("⅑"),
+        ("1", "10") => Some("⅒"),
+        _ => None,
+    };
+    if let Some(v) = vulgar {
+        return v.to_string();
+    }
+    format!("{}/{}", fraction_part(num), fraction_part(den))
+}
+
+/// Parenthesize a fraction operand unless it is a single term.
+fn fraction_part(s: &str) -> String {
+    let s = s.trim();
+    let mut depth = 0i32;
+    let mut compound = false;
+    for ch in s.chars() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ' ' | '+' | '-' | '−' | '=' | '/' | '±' | '·' | '×' | ',' if depth == 0 => {
+                compound = true;
+            }
+            _ => {}
+        }
+    }
+    if compound {
+        format!("({})", s)
+    } else {
+        s.to_string()
+    }
+}
+
+/// True if the whole string is one parenthesized group, e.g. "(n+1)".
+fn is_wrapped(s: &str) -> bool {
+    if !(s.starts_with('(') && s.ends_with(')')) {
+        return false;
+    }
+    let mut depth = 0i32;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 && i + 1 < s.len() {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    true
+}
+
+fn accent(arg: &str, mark: char) -> String {
+    if arg.chars().count() == 1 {
+        format!("{}{}", arg, mark)
+    } else {
+        // Combining marks only read well on a single character
+        format!("{}({})", mark_name(mark), arg)
+    }
+}
+
+fn mark_name(mark: char) -> &'static str {
+    match mark {
+        '\u{0302}' => "hat",
+        '\u{0304}' => "bar",
+        '\u{0303}' => "tilde",
+        '\u{0307}' => "dot",
+        _ => "vec",
+    }
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'N' => 'ℕ',
+        'Z' => 'ℤ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'C' => 'ℂ',
+        'P' => 'ℙ',
+        'H' => 'ℍ',
+        _ => c,
+    }
+}
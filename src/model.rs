@@ -1,5 +1,6 @@
+use chrono::TimeDelta;
 use chrono::{DateTime, FixedOffset};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Frontmatter {
@@ -8,6 +9,131 @@ pub struct Frontmatter {
     pub end: DateTime<FixedOffset>,
     #[serde(default)]
     pub acknowledgment: Option<AckConfig>,
+    /// Instructor's public key (age or OpenSSH). When set, the submission is
+    /// encrypted to it so only the instructor can read the answers.
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// Countdown warnings, e.g. `[30m, 10m, 2m]`.
+    #[serde(default = "default_warnings")]
+    pub warnings: Vec<Warning>,
+    #[serde(default)]
+    pub on_expire: OnExpire,
+    /// Late period after `end`, in seconds, e.g. `grace: 15m` or `grace: 900`.
+    /// Work in it is accepted but marked late; `on_expire` applies when it
+    /// ends. At most [`MAX_GRACE_SECS`].
+    #[serde(default, deserialize_with = "deserialize_grace")]
+    pub grace: i64,
+    /// Points for the whole quiz. Must match the sum of question `points`
+    /// when those are given.
+    #[serde(default)]
+    pub total_points: Option<f64>,
+}
+
+impl Frontmatter {
+    /// When late work stops being accepted and `on_expire` applies.
+    pub fn grace_end(&self) -> DateTime<FixedOffset> {
+        // Only fails with `end` at the edge of representable time
+        TimeDelta::try_seconds(self.grace)
+            .and_then(|grace| self.end.checked_add_signed(grace))
+            .unwrap_or(self.end)
+    }
+}
+
+/// The longest `grace` a quiz may give: a week.
+pub const MAX_GRACE_SECS: i64 = 7 * 24 * 3600;
+
+/// Frontmatter form of `grace`: `15m`, or a number of seconds.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GraceSpec {
+    Secs(i64),
+    Text(String),
+}
+
+fn deserialize_grace<'de, D: Deserializer<'de>>(d: D) -> Result<i64, D::Error> {
+    let (secs, spec) = match GraceSpec::deserialize(d)? {
+        GraceSpec::Secs(secs) => (Some(secs), secs.to_string()),
+        GraceSpec::Text(spec) => (crate::clock::parse_duration(&spec), spec),
+    };
+    secs.filter(|s| (0..=MAX_GRACE_SECS).contains(s))
+        .ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "invalid grace '{}', expected e.g. 15m or a number of seconds up to a week",
+                spec
+            ))
+        })
+}
+
+fn default_warnings() -> Vec<Warning> {
+    vec![Warning {
+        secs: 120,
+        style: WarningStyle::Dialog,
+    }]
+}
+
+/// A warning shown when this much time remains.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "WarningSpec", into = "WarningSpec")]
+pub struct Warning {
+    pub secs: i64,
+    pub style: WarningStyle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningStyle {
+    /// A dialog the student has to dismiss.
+    #[default]
+    Dialog,
+    /// Only flash the titlebar timer.
+    Flash,
+}
+
+/// Frontmatter form of a warning: `10m`, or `{at: 10m, style: flash}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum WarningSpec {
+    At(String),
+    Detailed {
+        at: String,
+        #[serde(default)]
+        style: WarningStyle,
+    },
+}
+
+impl TryFrom<WarningSpec> for Warning {
+    type Error = String;
+
+    fn try_from(spec: WarningSpec) -> Result<Self, String> {
+        let (at, style) = match spec {
+            WarningSpec::At(at) => (at, WarningStyle::Dialog),
+            WarningSpec::Detailed { at, style } => (at, style),
+        };
+        let secs = crate::clock::parse_duration(&at)
+            .filter(|s| *s > 0)
+            .ok_or_else(|| format!("invalid warning time '{}', expected e.g. 10m or 1h30m", at))?;
+        Ok(Warning { secs, style })
+    }
+}
+
+impl From<Warning> for WarningSpec {
+    fn from(w: Warning) -> Self {
+        WarningSpec::Detailed {
+            at: format!("{}s", w.secs),
+            style: w.style,
+        }
+    }
+}
+
+/// What happens when time runs out mid-exam, after any `grace` period.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnExpire {
+    /// Submit automatically.
+    #[default]
+    Submit,
+    /// Stop editing and write `response/` without committing or pushing.
+    Lock,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,30 +148,112 @@ pub struct Quiz {
     pub title: String,
     pub preamble: Vec<String>,
     pub questions: Vec<Question>,
+    /// From `:::section` directives; empty if the quiz has none.
+    pub sections: Vec<Section>,
     pub quiz_file: String,
     pub quiz_hash: String,
 }
 
+impl Quiz {
+    /// The quiz total: from the frontmatter, else the sum of question
+    /// points if any question has them.
+    pub fn total_points(&self) -> Option<f64> {
+        self.frontmatter.total_points.or_else(|| {
+            let points: Vec<f64> = self.questions.iter().filter_map(|q| q.points).collect();
+            (!points.is_empty()).then(|| points.iter().sum())
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Question {
     pub number: u32,
+    /// Explicit ID from the heading, e.g. `## 3. Title {#q-ownership}`.
+    pub id: Option<String>,
     pub title: String,
     pub body_lines: Vec<BodyElement>,
     pub kind: QuestionKind,
-    pub hints: Vec<String>,
+    pub hints: Vec<Hint>,
+    /// Weights from the heading, e.g. `## 3. Title {points=5 minutes=10}`.
+    pub points: Option<f64>,
+    /// Estimated minutes to answer; drives the pacing indicator.
+    pub minutes: Option<f64>,
+    /// Index into `Quiz::sections`.
+    pub section: Option<usize>,
+}
+
+impl Question {
+    /// Key used in saved state, submissions and the `files/` directory:
+    /// the explicit ID if given, else `q<number>`. Display numbers can change
+    /// between quiz revisions; IDs should not.
+    pub fn key(&self) -> String {
+        self.id
+            .clone()
+            .unwrap_or_else(|| format!("q{}", self.number))
+    }
+}
+
+/// Where the student is with a section; both times are RFC 3339.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SectionProgress {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// Set when the student moved on or its time ran out; never cleared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_at: Option<String>,
+}
+
+/// A run of questions that locks once the student moves past it or its
+/// time runs out.
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub title: String,
+    /// Seconds from entering the section.
+    pub time_limit: Option<i64>,
+}
+
+/// Format a point value without a trailing ".0" for whole numbers.
+pub fn format_points(points: f64) -> String {
+    if points.fract() == 0.0 {
+        format!("{}", points as i64)
+    } else {
+        format!("{}", points)
+    }
+}
+
+/// "1 point", "2.5 points".
+pub fn points_label(points: f64) -> String {
+    let unit = if points == 1.0 { "point" } else { "points" };
+    format!("{} {}", format_points(points), unit)
+}
+
+#[derive(Debug, Clone)]
+pub struct Hint {
+    pub text: String,
+    /// Points deducted when revealed, from `:::hint cost=0.5`.
+    pub cost: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum BodyElement {
     Text(String),
-    Code(String),
+    Code(CodeBlock),
     Bold(String),
     Italic(String),
     InlineCode(String),
     ListItem(String),
 }
 
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    /// Language tag from the fence info string (e.g. "rust"), lowercased.
+    pub lang: Option<String>,
+    pub text: String,
+    /// Set by a `numbered` flag after the language, e.g. ```` ```python numbered ````.
+    pub line_numbers: bool,
+}
+
 #[derive(Debug, Clone)]
 pub enum QuestionKind {
     SingleChoice(Vec<Choice>),
@@ -55,6 +263,19 @@ pub enum QuestionKind {
     File(FileConstraints),
 }
 
+impl QuestionKind {
+    /// Type name as written to answers.yaml.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            QuestionKind::SingleChoice(_) => "single",
+            QuestionKind::MultiChoice(_) => "multi",
+            QuestionKind::Short => "short",
+            QuestionKind::Long => "long",
+            QuestionKind::File(_) => "file",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Choice {
     pub label: char,
@@ -63,24 +284,14 @@ pub struct Choice {
     pub marked: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FileConstraints {
     pub max_files: Option<u32>,
     pub max_size: Option<u64>,
     pub accept: Vec<String>,
 }
 
-impl Default for FileConstraints {
-    fn default() -> Self {
-        Self {
-            max_files: None,
-            max_size: None,
-            accept: Vec::new(),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Answer {
     #[serde(rename = "type")]
     pub answer_type: String,
@@ -92,6 +303,14 @@ pub struct Answer {
     pub files: Option<Vec<String>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HintReveal {
+    pub level: usize,
+    pub revealed_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AckData {
     pub name: String,
@@ -1,26 +1,51 @@
-use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
 
+use crate::encrypt;
+use crate::math;
 use crate::model::*;
 
 pub fn parse_quiz(content: &str, quiz_file: &str, quiz_hash: &str) -> Result<Quiz, String> {
     let (frontmatter, body) = split_frontmatter(content)?;
     let fm: Frontmatter =
         serde_yaml::from_str(&frontmatter).map_err(|e| format!("Invalid frontmatter: {}", e))?;
+    if let Some(recipient) = &fm.recipient {
+        encrypt::parse_recipient(recipient)?;
+    }
 
-    let (title, preamble, questions) = parse_body(&body)?;
+    let (title, preamble, questions, sections) = parse_body(&body)?;
 
     let title = fm.title.clone().unwrap_or(title);
 
+    if let Some(total) = fm.total_points {
+        let declared: Vec<f64> = questions.iter().filter_map(|q| q.points).collect();
+        let sum: f64 = declared.iter().sum();
+        if !declared.is_empty() && (sum - total).abs() > 1e-9 {
+            return Err(format!(
+                "total_points is {} but the question points add up to {}",
+                total, sum
+            ));
+        }
+    }
+
     Ok(Quiz {
         frontmatter: fm,
         title,
         preamble,
         questions,
+        sections,
         quiz_file: quiz_file.to_string(),
         quiz_hash: quiz_hash.to_string(),
     })
 }
 
+/// Whether `content` opens with a frontmatter block: `---`, a YAML mapping,
+/// then a closing `---`. A bank that merely starts with a horizontal rule
+/// does not count.
+pub fn has_frontmatter(content: &str) -> bool {
+    split_frontmatter(content)
+        .is_ok_and(|(fm, _)| serde_yaml::from_str::<serde_yaml::Mapping>(&fm).is_ok())
+}
+
 fn split_frontmatter(content: &str) -> Result<(String, String), String> {
     let trimmed = content.trim_start();
     if !trimmed.starts_with("---") {
@@ -38,10 +63,14 @@ fn split_frontmatter(content: &str) -> Result<(String, String), String> {
     Ok((fm, body))
 }
 
-fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String> {
+type ParsedBody = (String, Vec<String>, Vec<Question>, Vec<Section>);
+
+fn parse_body(body: &str) -> Result<ParsedBody, String> {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_TASKLISTS);
     opts.insert(Options::ENABLE_STRIKETHROUGH);
+    opts.insert(Options::ENABLE_MATH);
+    opts.insert(Options::ENABLE_HEADING_ATTRIBUTES);
 
     let parser = Parser::new_ext(body, opts);
     let events: Vec<Event> = parser.collect();
@@ -49,21 +78,26 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
     let mut title = String::new();
     let mut preamble: Vec<String> = Vec::new();
     let mut questions: Vec<Question> = Vec::new();
+    // Each section with the index of its first question
+    let mut sections: Vec<(Section, usize)> = Vec::new();
 
     let mut in_h1 = false;
     let mut in_h2 = false;
     let mut current_h2_text = String::new();
+    let mut current_id: Option<String> = None;
+    let mut current_attrs: Vec<(String, Option<String>)> = Vec::new();
     let mut seen_h2 = false;
 
     // Collect content between questions as raw sections
     let mut current_choices: Vec<Choice> = Vec::new();
     let mut current_kind: Option<QuestionKind> = None;
-    let mut current_hints: Vec<String> = Vec::new();
+    let mut current_hints: Vec<Hint> = Vec::new();
     let mut current_body: Vec<BodyElement> = Vec::new();
     let mut in_blockquote = false;
     let mut blockquote_text = String::new();
     let mut in_hint_block = false;
     let mut hint_text = String::new();
+    let mut hint_cost: Option<f64> = None;
     let mut choice_index: u8 = 0;
     let mut in_list_item = false;
     let mut list_item_text = String::new();
@@ -72,12 +106,15 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
     let mut paragraph_text = String::new();
     let mut in_code_block = false;
     let mut code_block_text = String::new();
+    let mut code_block_info = String::new();
 
     let mut i = 0;
     while i < events.len() {
         let event = &events[i];
         match event {
-            Event::Start(Tag::Heading { level, .. }) => {
+            Event::Start(Tag::Heading {
+                level, id, attrs, ..
+            }) => {
                 match level {
                     pulldown_cmark::HeadingLevel::H1 => {
                         in_h1 = true;
@@ -87,6 +124,8 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                         if seen_h2 {
                             finalize_question(
                                 &current_h2_text,
+                                current_id.take(),
+                                std::mem::take(&mut current_attrs),
                                 &mut questions,
                                 &mut current_choices,
                                 &mut current_kind,
@@ -97,6 +136,11 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                         }
                         in_h2 = true;
                         current_h2_text = String::new();
+                        current_id = id.as_ref().map(|id| id.to_string());
+                        current_attrs = attrs
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), v.as_ref().map(|v| v.to_string())))
+                            .collect();
                         seen_h2 = true;
                     }
                     _ => {}
@@ -185,6 +229,11 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                     if text.starts_with(":::hint") {
                         in_hint_block = true;
                         hint_text = String::new();
+                        hint_cost = parse_hint_cost(&text, &current_h2_text)?;
+                    } else if text.starts_with(":::section") {
+                        // Starts with the next question
+                        let first = questions.len() + usize::from(seen_h2);
+                        sections.push((parse_section(&text)?, first));
                     } else if text == ":::" && in_hint_block {
                         // end hint - handled below
                     } else if !seen_h2 && !in_h1 {
@@ -194,21 +243,28 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                     }
                 }
             }
-            Event::Start(Tag::CodeBlock(_)) => {
+            Event::Start(Tag::CodeBlock(kind)) => {
                 in_code_block = true;
                 code_block_text = String::new();
+                code_block_info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
             }
             Event::End(TagEnd::CodeBlock) => {
                 in_code_block = false;
                 if seen_h2 {
-                    current_body.push(BodyElement::Code(code_block_text.clone()));
+                    current_body.push(BodyElement::Code(parse_code_block(
+                        &code_block_info,
+                        &code_block_text,
+                    )));
                 }
             }
             Event::Text(text) => {
                 let t = text.to_string();
 
                 if in_h1 {
-                    title = t;
+                    title.push_str(&t);
                 } else if in_h2 {
                     current_h2_text.push_str(&t);
                 } else if in_code_block {
@@ -222,13 +278,15 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                     if t.trim().starts_with(":::hint") {
                         in_hint_block = true;
                         hint_text = String::new();
+                        hint_cost = parse_hint_cost(&t, &current_h2_text)?;
                         paragraph_text = String::new();
                     } else if t.trim() == ":::" && in_hint_block {
                         in_hint_block = false;
-                        if !hint_text.is_empty() {
-                            if seen_h2 {
-                                current_hints.push(hint_text.trim().to_string());
-                            }
+                        if !hint_text.is_empty() && seen_h2 {
+                            current_hints.push(Hint {
+                                text: hint_text.trim().to_string(),
+                                cost: hint_cost.take(),
+                            });
                         }
                         hint_text = String::new();
                         paragraph_text = String::new();
@@ -251,11 +309,34 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
                     list_item_text.push_str(&c);
                 }
             }
-            Event::SoftBreak | Event::HardBreak => {
-                if in_paragraph {
-                    paragraph_text.push(' ');
+            Event::InlineMath(latex) | Event::DisplayMath(latex) => {
+                let unicode = math::to_unicode(latex);
+                if in_h1 {
+                    title.push_str(&unicode);
+                } else if in_h2 {
+                    current_h2_text.push_str(&unicode);
+                } else if in_blockquote {
+                    blockquote_text.push_str(&unicode);
+                } else if in_list_item {
+                    list_item_text.push_str(&unicode);
+                } else if in_hint_block {
+                    hint_text.push_str(&unicode);
+                } else if in_paragraph && seen_h2 {
+                    // Question bodies go through ui::markdown, which renders
+                    // the math itself, so keep the source delimiters
+                    let delim = if matches!(event, Event::DisplayMath(_)) {
+                        "$$"
+                    } else {
+                        "$"
+                    };
+                    paragraph_text.push_str(&format!("{}{}{}", delim, latex, delim));
+                } else if in_paragraph {
+                    paragraph_text.push_str(&unicode);
                 }
             }
+            Event::SoftBreak | Event::HardBreak if in_paragraph => {
+                paragraph_text.push(' ');
+            }
             Event::Rule => {
                 // Horizontal rule - ignore (visual separator)
             }
@@ -268,6 +349,8 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
     if seen_h2 {
         finalize_question(
             &current_h2_text,
+            current_id.take(),
+            std::mem::take(&mut current_attrs),
             &mut questions,
             &mut current_choices,
             &mut current_kind,
@@ -277,20 +360,141 @@ fn parse_body(body: &str) -> Result<(String, Vec<String>, Vec<Question>), String
         )?;
     }
 
-    Ok((title, preamble, questions))
+    check_unique_keys(&questions)?;
+    let sections = assign_sections(&mut questions, sections)?;
+
+    Ok((title, preamble, questions, sections))
+}
+
+/// Parse a section directive, e.g. ":::section time=30m Part A: Closed book".
+fn parse_section(marker: &str) -> Result<Section, String> {
+    let rest = marker.strip_prefix(":::section").unwrap_or_default();
+    let mut time_limit = None;
+    let mut title = Vec::new();
+    for word in rest.split_whitespace() {
+        match word.strip_prefix("time=") {
+            Some(limit) => {
+                let secs = crate::clock::parse_duration(limit).filter(|s| *s > 0);
+                time_limit = Some(secs.ok_or_else(|| {
+                    format!("Invalid section time '{}', expected e.g. 30m", limit)
+                })?);
+            }
+            None => title.push(word),
+        }
+    }
+    if title.is_empty() {
+        return Err(format!("Section needs a title: {}", marker));
+    }
+    Ok(Section {
+        title: title.join(" "),
+        time_limit,
+    })
+}
+
+/// Give each question the section it falls under. Once a quiz uses
+/// sections, every question must be in one.
+fn assign_sections(
+    questions: &mut [Question],
+    sections: Vec<(Section, usize)>,
+) -> Result<Vec<Section>, String> {
+    if sections.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Some(q) = questions.first().filter(|_| sections[0].1 > 0) {
+        return Err(format!(
+            "Question {} comes before the first :::section",
+            q.number
+        ));
+    }
+    for (idx, (section, first)) in sections.iter().enumerate() {
+        // Saved progress is matched to sections by title
+        if sections[..idx]
+            .iter()
+            .any(|(s, _)| s.title == section.title)
+        {
+            return Err(format!("Duplicate section title '{}'", section.title));
+        }
+        let end = sections.get(idx + 1).map_or(questions.len(), |next| next.1);
+        if *first >= end {
+            return Err(format!("Section '{}' has no questions", section.title));
+        }
+        for q in &mut questions[*first..end] {
+            q.section = Some(idx);
+        }
+    }
+    Ok(sections.into_iter().map(|(section, _)| section).collect())
+}
+
+/// Saved answers are keyed by question ID, and the live state by question
+/// number, so two questions must never share either.
+fn check_unique_keys(questions: &[Question]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    let mut numbers = std::collections::HashSet::new();
+    for q in questions {
+        let key = q.key();
+        if !seen.insert(key.clone()) {
+            return Err(format!(
+                "Duplicate question ID '{}' (question {})",
+                key, q.number
+            ));
+        }
+        if !numbers.insert(q.number) {
+            return Err(format!("Duplicate question number {}", q.number));
+        }
+    }
+    Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn finalize_question(
     h2_text: &str,
+    id: Option<String>,
+    attrs: Vec<(String, Option<String>)>,
     questions: &mut Vec<Question>,
     choices: &mut Vec<Choice>,
     kind: &mut Option<QuestionKind>,
-    hints: &mut Vec<String>,
+    hints: &mut Vec<Hint>,
     body: &mut Vec<BodyElement>,
     choice_index: &mut u8,
 ) -> Result<(), String> {
     let (number, title) = parse_h2_title(h2_text)?;
 
+    if let Some(ref id) = id {
+        // IDs name directories under files/, so keep them to plain names
+        let allowed = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        if id.is_empty() || !id.chars().all(allowed) {
+            return Err(format!(
+                "Invalid question ID {{#{}}} on question {}",
+                id, number
+            ));
+        }
+    }
+
+    let mut points = None;
+    let mut minutes = None;
+    for (key, value) in &attrs {
+        let slot = match key.as_str() {
+            "points" => &mut points,
+            "minutes" => &mut minutes,
+            _ => continue,
+        };
+        let parsed = value
+            .as_deref()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite() && *v >= 0.0);
+        match parsed {
+            Some(v) => *slot = Some(v),
+            None => {
+                return Err(format!(
+                    "Invalid {}={} on question {}: expected a number",
+                    key,
+                    value.as_deref().unwrap_or(""),
+                    number
+                ))
+            }
+        }
+    }
+
     let is_multi = title.contains("(Multi)");
 
     let final_kind = if !choices.is_empty() {
@@ -305,10 +509,14 @@ fn finalize_question(
 
     questions.push(Question {
         number,
+        id,
         title: title.to_string(),
         body_lines: std::mem::take(body),
         kind: final_kind,
         hints: std::mem::take(hints),
+        points,
+        minutes,
+        section: None,
     });
 
     *choice_index = 0;
@@ -333,6 +541,47 @@ fn parse_h2_title(text: &str) -> Result<(u32, String), String> {
     }
 }
 
+/// Build a code block from its fence info string, e.g. "rust" or "python numbered".
+fn parse_code_block(info: &str, text: &str) -> CodeBlock {
+    let words: Vec<&str> = info
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let line_numbers = words.contains(&"numbered");
+    let lang = words
+        .first()
+        .filter(|w| **w != "numbered")
+        .map(|w| w.to_lowercase());
+    CodeBlock {
+        lang,
+        text: text.to_string(),
+        line_numbers,
+    }
+}
+
+/// Parse the attributes of a hint opener, e.g. ":::hint cost=0.5", under
+/// the question whose heading is `heading`.
+fn parse_hint_cost(marker: &str, heading: &str) -> Result<Option<f64>, String> {
+    let Some(attrs) = marker.trim().strip_prefix(":::hint") else {
+        return Ok(None);
+    };
+    let Some((_, value)) = attrs
+        .split_whitespace()
+        .filter_map(|attr| attr.split_once('='))
+        .find(|(key, _)| key.trim() == "cost")
+    else {
+        return Ok(None);
+    };
+    match value.trim().parse::<f64>() {
+        Ok(cost) if cost.is_finite() && cost >= 0.0 => Ok(Some(cost)),
+        _ => Err(format!(
+            "Invalid cost={} on question {}: expected a number",
+            value,
+            heading.trim().split('.').next().unwrap_or("")
+        )),
+    }
+}
+
 fn parse_file_constraints(text: &str) -> FileConstraints {
     let mut constraints = FileConstraints::default();
 
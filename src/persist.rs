@@ -1,187 +1,501 @@
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+use directories::ProjectDirs;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 
-use crate::model::AckData;
-use crate::state::AppState;
+use crate::export::{self, ExportFormat};
+use crate::git;
+use crate::journal;
+use crate::model::{Answer, Question, QuestionKind};
+use crate::schema::{self, AnswerValue, AnswersDoc, QuestionRecord};
+use crate::state::{AppState, QuestionTiming, StatusCounts};
 use crate::submit;
 
+/// Working state directory for a repo: `$TERMQUIZ_STATE/<repo-path-hash>/`,
+/// else `~/.local/state/termquiz/<repo-path-hash>/` (or the platform equivalent).
+/// Nothing is written into the repo until submit.
+pub fn state_dir_for(repo_dir: &Path) -> PathBuf {
+    let canonical = repo_dir
+        .canonicalize()
+        .unwrap_or_else(|_| repo_dir.to_path_buf());
+    let hash = compute_str_hash(&canonical.to_string_lossy());
+    let short = hash.trim_start_matches("sha256:")[..16].to_string();
+
+    let base = match std::env::var_os("TERMQUIZ_STATE") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => ProjectDirs::from("", "", "termquiz")
+            .map(|dirs| {
+                dirs.state_dir()
+                    .unwrap_or_else(|| dirs.data_local_dir())
+                    .to_path_buf()
+            })
+            .unwrap_or_else(|| std::env::temp_dir().join("termquiz")),
+    };
+    base.join(short)
+}
+
 pub fn save_state(state: &AppState) -> Result<(), String> {
-    let response_dir = state.repo_dir.join("response");
-    fs::create_dir_all(&response_dir)
-        .map_err(|e| format!("Cannot create response dir: {}", e))?;
+    save_doc(&state.state_dir, &submit::build_answers_doc(state))
+}
 
-    let yaml = submit::build_answers_yaml(state);
-    atomic_write(&response_dir.join("answers.yaml"), &yaml)?;
+/// Write `doc` as the answers.yaml snapshot in `state_dir`, then rotate the
+/// journal entries it now includes out of the way.
+pub fn save_doc(state_dir: &Path, doc: &AnswersDoc) -> Result<(), String> {
+    fs::create_dir_all(state_dir).map_err(|e| format!("Cannot create state dir: {}", e))?;
+
+    let yaml = schema::to_yaml(doc)?;
+    atomic_write(&state_dir.join("answers.yaml"), &yaml)?;
+    if let Some(seq) = doc.session.journal_seq {
+        journal::rotate(state_dir, seq)?;
+    }
 
     Ok(())
 }
 
-pub fn load_state(state: &mut AppState) -> Result<bool, String> {
-    let yaml_path = state.repo_dir.join("response").join("answers.yaml");
-    if !yaml_path.exists() {
+/// Earlier versions kept the draft in `response/answers.yaml` inside the
+/// repo, which then looked like a submission. Move such a draft (and its
+/// staged files) into the state directory, unless it was really submitted.
+pub fn adopt_legacy_draft(repo_dir: &Path, state_dir: &Path) -> Result<bool, String> {
+    let response_dir = repo_dir.join("response");
+    let draft = response_dir.join("answers.yaml");
+    if !draft.exists()
+        || state_dir.join("answers.yaml").exists()
+        || git::has_response_in_history(repo_dir)
+    {
+        return Ok(false);
+    }
+    let content = fs::read_to_string(&draft)
+        .map_err(|e| format!("Cannot read {}: {}", draft.display(), e))?;
+    let doc = schema::from_yaml(&content)?;
+    if doc.quiz.submitted_at != "unknown" {
         return Ok(false);
     }
 
-    let content = fs::read_to_string(&yaml_path)
-        .map_err(|e| format!("Cannot read answers.yaml: {}", e))?;
+    fs::create_dir_all(state_dir).map_err(|e| format!("Cannot create state dir: {}", e))?;
+    fs::rename(&draft, state_dir.join("answers.yaml"))
+        .map_err(|e| format!("Cannot move draft to {}: {}", state_dir.display(), e))?;
+    if response_dir.join("files").exists() && !state_dir.join("files").exists() {
+        fs::rename(response_dir.join("files"), state_dir.join("files"))
+            .map_err(|e| format!("Cannot move staged files: {}", e))?;
+    }
+    let _ = fs::remove_dir(&response_dir);
+    Ok(true)
+}
 
-    let doc: serde_yaml::Value = serde_yaml::from_str(&content)
-        .map_err(|e| format!("Corrupt answers.yaml: {} (use --clear to reset)", e))?;
+pub fn load_state(state: &mut AppState) -> Result<bool, String> {
+    let yaml_path = state.state_dir.join("answers.yaml");
+    let snapshot = if yaml_path.exists() {
+        fs::read_to_string(&yaml_path)
+            .map_err(|e| format!("Cannot read answers.yaml: {}", e))
+            .and_then(|content| schema::from_yaml(&content))
+    } else {
+        Err("answers.yaml is missing".to_string())
+    };
 
-    // Verify quiz hash
-    if let Some(hash) = doc["session"]["quiz_file_hash"].as_str() {
-        if hash != state.quiz.quiz_hash {
-            return Err("Quiz file has changed since last session. Use --clear to reset.".to_string());
+    // A snapshot torn by a crash can be rebuilt from the whole journal; the
+    // student sees a note about it once the quiz opens
+    let mut recovered = None;
+    let (mut doc, entries) = match snapshot {
+        Ok(doc) => (doc, journal::read_tail(&state.state_dir)?),
+        Err(e) => {
+            let entries = journal::read_entries(&state.state_dir)?;
+            if entries.is_empty() {
+                if !yaml_path.exists() {
+                    return Ok(false);
+                }
+                return Err(format!("{} (use --clear to reset)", e));
+            }
+            recovered = Some(format!("{}; answers were recovered from the journal", e));
+            (journal::cleared(&submit::build_answers_doc(state)), entries)
         }
-    }
+    };
+    let after = doc.session.journal_seq.unwrap_or(0);
+    journal::replay(&mut doc, &entries, after, None);
+    restore_from_doc(state, &doc);
+    state.migration_notes.extend(recovered);
+
+    let last_seq = entries.iter().map(|e| e.seq).max().unwrap_or(0);
+    state.journal.seq = last_seq.max(doc.session.journal_seq.unwrap_or(0));
+    journal::mark_synced(state);
+
+    Ok(true)
+}
+
+/// Apply a parsed answers document to the state.
+pub fn restore_from_doc(state: &mut AppState, doc: &AnswersDoc) {
+    // If the quiz was edited since the last session, carry over what still
+    // applies and record what was dropped
+    let migrating = doc.session.quiz_file_hash != state.quiz.quiz_hash;
+    let mut notes: Vec<String> = Vec::new();
 
     // Restore session metadata
-    if let Some(v) = doc["session"]["current_question"].as_u64() {
-        if (v as usize) < state.quiz.questions.len() {
-            state.current_question = v as usize;
-        }
+    if doc.session.current_question < state.quiz.questions.len() {
+        state.current_question = doc.session.current_question;
     }
-    if let Some(v) = doc["quiz"]["submitted_at"].as_str() {
-        if v != "unknown" {
-            state.submitted_at = Some(v.to_string());
-        }
+    if doc.quiz.submitted_at != "unknown" {
+        state.submitted_at = Some(doc.quiz.submitted_at.clone());
+    }
+    if doc.session.started_at.is_some() {
+        state.started_at = doc.session.started_at.clone();
     }
-    if let Some(v) = doc["session"]["started_at"].as_str() {
-        state.started_at = Some(v.to_string());
+    if let Some(ack) = doc
+        .session
+        .acknowledgment
+        .as_ref()
+        .filter(|a| !a.name.is_empty())
+    {
+        state.ack_data = Some(ack.clone());
+    }
+    // Clock events only accumulate; an older backup cannot drop them
+    for event in &doc.clock_events {
+        if !state.clock_events.contains(event) {
+            state.clock_events.push(event.clone());
+        }
     }
 
-    // Restore acknowledgment
-    if let Some(ack) = doc["session"]["acknowledgment"].as_mapping() {
-        let name = ack.get(serde_yaml::Value::String("name".into()))
-            .and_then(|v| v.as_str()).unwrap_or("").to_string();
-        let agreed_at = ack.get(serde_yaml::Value::String("agreed_at".into()))
-            .and_then(|v| v.as_str()).unwrap_or("").to_string();
-        let text_hash = ack.get(serde_yaml::Value::String("text_hash".into()))
-            .and_then(|v| v.as_str()).unwrap_or("").to_string();
-        if !name.is_empty() {
-            state.ack_data = Some(AckData { name, agreed_at, text_hash });
+    // Sections are matched by title, or by position if one was renamed in
+    // place; a lock is never undone
+    for (pos, record) in doc.sections.iter().enumerate() {
+        let by_title = state
+            .quiz
+            .sections
+            .iter()
+            .position(|s| s.title == record.title);
+        let renamed = || {
+            let section = state.quiz.sections.get(pos)?;
+            let saved = doc.sections.iter().any(|r| r.title == section.title);
+            (!saved).then_some(pos)
+        };
+        let Some(idx) = by_title.or_else(renamed) else {
+            continue;
+        };
+        let progress = &mut state.sections[idx];
+        if progress.started_at.is_none() {
+            progress.started_at = record.progress.started_at.clone();
+        }
+        if progress.locked_at.is_none() {
+            progress.locked_at = record.progress.locked_at.clone();
         }
     }
 
     // Restore per-question data
-    if let Some(questions) = doc["questions"].as_sequence() {
-        for q_val in questions {
-            let number = match q_val["number"].as_u64() {
-                Some(n) => n as u32,
-                None => continue,
-            };
-            let qtype = q_val["type"].as_str().unwrap_or("");
-
-            // Restore answer
-            let answer_val = &q_val["answer"];
-            if !answer_val.is_null() {
-                let answer = match qtype {
-                    "single" => {
-                        if let Some(label) = answer_val.as_str() {
-                            Some(crate::model::Answer {
-                                answer_type: "single".to_string(),
-                                selected: Some(vec![label.to_string()]),
-                                text: None,
-                                files: None,
-                            })
-                        } else { None }
-                    }
-                    "multi" => {
-                        if let Some(seq) = answer_val.as_sequence() {
-                            let labels: Vec<String> = seq.iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect();
-                            Some(crate::model::Answer {
-                                answer_type: "multi".to_string(),
-                                selected: Some(labels),
-                                text: None,
-                                files: None,
-                            })
-                        } else { None }
-                    }
-                    "short" => {
-                        if let Some(text) = answer_val.as_str() {
-                            Some(crate::model::Answer {
-                                answer_type: "short".to_string(),
-                                selected: None,
-                                text: Some(text.to_string()),
-                                files: None,
-                            })
-                        } else { None }
-                    }
-                    "long" => {
-                        if let Some(text) = answer_val.as_str() {
-                            Some(crate::model::Answer {
-                                answer_type: "long".to_string(),
-                                selected: None,
-                                text: Some(text.to_string()),
-                                files: None,
-                            })
-                        } else { None }
-                    }
-                    "file" => {
-                        if let Some(seq) = answer_val.as_sequence() {
-                            let files: Vec<String> = seq.iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect();
-                            Some(crate::model::Answer {
-                                answer_type: "file".to_string(),
-                                selected: None,
-                                text: None,
-                                files: Some(files),
-                            })
-                        } else { None }
+    for record in &doc.questions {
+        let Some(question) = find_question(state, record).cloned() else {
+            if record.answer.is_some() {
+                notes.push(format!(
+                    "Q{} {}: question was removed, answer discarded",
+                    record.number, record.title
+                ));
+            }
+            continue;
+        };
+        let number = question.number;
+        if record.qtype != question.kind.type_name() {
+            if record.answer.is_some() {
+                notes.push(format!(
+                    "Q{} {}: question type changed, answer discarded",
+                    number, question.title
+                ));
+            }
+            continue;
+        }
+
+        // Restore answer
+        let mut wording_changed = false;
+        let answer = record
+            .answer
+            .as_ref()
+            .and_then(|value| answer_from_value(&record.qtype, value))
+            .map(|mut a| {
+                // Saved file paths are relative to the state dir's staging area
+                if let Some(files) = a.files.as_mut() {
+                    for f in files.iter_mut() {
+                        *f = state.state_dir.join(&*f).to_string_lossy().to_string();
                     }
-                    _ => None,
-                };
-                if let Some(a) = answer {
-                    state.answers.insert(number, a);
-                    state.visited.insert(number, true);
                 }
+                a
+            });
+        let answer = match answer {
+            Some(a) if migrating => {
+                let (a, changed) = check_choices(a, &question, record, &mut notes);
+                wording_changed = changed;
+                a
             }
+            other => other,
+        };
+        if let Some(a) = answer {
+            state.answers.insert(number, a);
+            state.visited.insert(number, true);
+        }
 
-            // Restore done/flagged
-            if q_val["done"].as_bool().unwrap_or(false) {
-                state.done_marks.insert(number, true);
-            }
-            if q_val["flagged"].as_bool().unwrap_or(false) {
-                state.flags.insert(number, true);
-            }
+        // Restore done/flagged; a changed question goes back to flagged for review
+        if record.done && !wording_changed {
+            state.done_marks.insert(number, true);
+        }
+        if record.flagged || wording_changed {
+            state.flags.insert(number, true);
+        }
 
-            // Restore hint_used
-            if q_val["hint_used"].as_bool().unwrap_or(false) {
-                state.hints_revealed.insert(number, 1);
-            }
+        // Restore hint levels and their reveal log
+        if record.hints_used > 0 {
+            state
+                .hints_revealed
+                .insert(number, record.hints_used.min(question.hints.len()));
+        }
+        if !record.hint_reveals.is_empty() {
+            state
+                .hint_reveals
+                .insert(number, record.hint_reveals.clone());
+        }
+
+        // Time on task carries on from where the last session left it
+        if record.time_spent_seconds > 0
+            || record.first_visited_at.is_some()
+            || record.last_edited_at.is_some()
+        {
+            state.timing.insert(
+                number,
+                QuestionTiming {
+                    focused: std::time::Duration::from_secs(record.time_spent_seconds),
+                    first_visited_at: record.first_visited_at.clone(),
+                    last_edited_at: record.last_edited_at.clone(),
+                },
+            );
         }
     }
 
-    Ok(true)
+    if migrating {
+        if notes.is_empty() {
+            notes.push("All saved answers still apply.".to_string());
+        }
+        state.migration_notes = notes;
+    }
 }
 
-pub fn clear_state(repo_dir: &Path) -> Result<(), String> {
-    let response_dir = repo_dir.join("response");
-    if response_dir.exists() {
-        fs::remove_dir_all(&response_dir)
-            .map_err(|e| format!("Cannot clear state: {}", e))?;
+/// The question a saved record belongs to. Match by ID so renumbered
+/// questions keep their answers; files written before IDs existed only
+/// have the number.
+fn find_question<'a>(state: &'a AppState, record: &QuestionRecord) -> Option<&'a Question> {
+    match &record.id {
+        Some(id) => state.quiz.questions.iter().find(|q| q.key() == *id),
+        None => state
+            .quiz
+            .questions
+            .iter()
+            .find(|q| q.number == record.number),
+    }
+}
+
+fn answer_from_value(qtype: &str, value: &AnswerValue) -> Option<Answer> {
+    let mut answer = Answer {
+        answer_type: qtype.to_string(),
+        selected: None,
+        text: None,
+        files: None,
+    };
+    match qtype {
+        "single" => answer.selected = Some(vec![value.as_text()?.to_string()]),
+        "multi" => answer.selected = Some(value.as_list()?.to_vec()),
+        "short" | "long" => answer.text = Some(value.as_text()?.to_string()),
+        "file" => answer.files = Some(value.as_list()?.to_vec()),
+        _ => return None,
+    }
+    Some(answer)
+}
+
+/// Re-check a saved choice answer against the edited quiz. Labels that no
+/// longer exist are dropped. Also returns whether any choice's wording
+/// changed, in which case the caller flags the question for review.
+fn check_choices(
+    mut answer: Answer,
+    question: &Question,
+    record: &QuestionRecord,
+    notes: &mut Vec<String>,
+) -> (Option<Answer>, bool) {
+    let choices = match &question.kind {
+        QuestionKind::SingleChoice(c) | QuestionKind::MultiChoice(c) => c,
+        _ => return (Some(answer), false),
+    };
+    let selected = answer.selected.take().unwrap_or_default();
+
+    let (kept, removed): (Vec<String>, Vec<String>) = selected
+        .into_iter()
+        .partition(|label| choices.iter().any(|c| c.label.to_string() == *label));
+    if !removed.is_empty() {
+        notes.push(format!(
+            "Q{} {}: choice {} no longer exists, deselected",
+            question.number,
+            question.title,
+            removed.join(", ")
+        ));
+    }
+
+    let changed: Vec<String> = choices
+        .iter()
+        .filter(|c| {
+            record
+                .choices
+                .as_ref()
+                .and_then(|saved| saved.get(&c.label.to_string()))
+                .is_some_and(|old| *old != c.text)
+        })
+        .map(|c| c.label.to_string())
+        .collect();
+    let wording_changed = !changed.is_empty() && !kept.is_empty();
+    if wording_changed {
+        notes.push(format!(
+            "Q{} {}: wording of choice {} changed, flagged for review",
+            question.number,
+            question.title,
+            changed.join(", ")
+        ));
+    }
+
+    if kept.is_empty() {
+        return (None, wording_changed);
+    }
+    answer.selected = Some(kept);
+    (Some(answer), wording_changed)
+}
+
+pub fn clear_state(state_dir: &Path) -> Result<(), String> {
+    if state_dir.exists() {
+        fs::remove_dir_all(state_dir).map_err(|e| format!("Cannot clear state: {}", e))?;
     }
     Ok(())
 }
 
+/// Remove the draft and staged files after a successful submission, keeping
+/// the journal so `termquiz replay` can still answer questions about it.
+pub fn clear_draft(state_dir: &Path) -> Result<(), String> {
+    let yaml = state_dir.join("answers.yaml");
+    if yaml.exists() {
+        fs::remove_file(&yaml).map_err(|e| format!("Cannot clear draft: {}", e))?;
+    }
+    let files = state_dir.join("files");
+    if files.exists() {
+        fs::remove_dir_all(&files).map_err(|e| format!("Cannot clear draft: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Write via a temp file, fsyncing both the file and the rename so a crash
+/// leaves either the old or the new content on disk.
 fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
     let tmp = path.with_extension("tmp");
-    fs::write(&tmp, content).map_err(|e| format!("Cannot write {}: {}", tmp.display(), e))?;
+    let mut file =
+        fs::File::create(&tmp).map_err(|e| format!("Cannot write {}: {}", tmp.display(), e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Cannot write {}: {}", tmp.display(), e))?;
     fs::rename(&tmp, path).map_err(|e| format!("Cannot rename: {}", e))?;
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
     Ok(())
 }
 
-pub fn export_answers(state: &AppState, path: &str) -> Result<(), String> {
-    let yaml = submit::build_answers_yaml(state);
-    fs::write(path, &yaml).map_err(|e| format!("Cannot export: {}", e))?;
+pub fn export_answers(state: &AppState, path: &str, format: ExportFormat) -> Result<(), String> {
+    let content = export::render(state, format)?;
+    fs::write(path, &content).map_err(|e| format!("Cannot export: {}", e))?;
     Ok(())
 }
 
+/// Read an `--export` backup and check it belongs to this quiz. Returns the
+/// document to merge and one line per saved answer it would overwrite.
+pub fn read_import(state: &AppState, path: &Path) -> Result<(AnswersDoc, Vec<String>), String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    let mut doc = schema::from_yaml(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    // A different quiz version is fine as long as every answer still fits
+    if doc.session.quiz_file_hash != state.quiz.quiz_hash {
+        if doc.quiz.title != state.quiz.title {
+            return Err(format!(
+                "{} is an export of '{}', not '{}'",
+                path.display(),
+                doc.quiz.title,
+                state.quiz.title
+            ));
+        }
+        let misfits: Vec<String> = doc
+            .questions
+            .iter()
+            .filter(|r| r.answer.is_some())
+            .filter(|r| find_question(state, r).is_none_or(|q| q.kind.type_name() != r.qtype))
+            .map(|r| format!("Q{} {}", r.number, r.title))
+            .collect();
+        if !misfits.is_empty() {
+            return Err(format!(
+                "{} does not match the current quiz; no matching question for: {}",
+                path.display(),
+                misfits.join(", ")
+            ));
+        }
+    }
+
+    let current = submit::build_answers_doc(state);
+    let mut lines = Vec::new();
+    for record in doc.questions.iter_mut() {
+        let Some(question) = find_question(state, record) else {
+            continue;
+        };
+        // The export only names attached files; their contents stay behind
+        if record.qtype == "file" && record.answer.is_some() {
+            record.answer = None;
+            lines.push(format!(
+                "Q{} {}: attached files are not part of the export, attach them again",
+                question.number, question.title
+            ));
+            continue;
+        }
+        let old = current
+            .questions
+            .iter()
+            .find(|r| r.number == question.number)
+            .and_then(|r| r.answer.as_ref());
+        if let (Some(old), Some(new)) = (old, record.answer.as_ref()) {
+            if old != new {
+                lines.push(format!(
+                    "Q{} {}: {} -> {}",
+                    question.number,
+                    question.title,
+                    describe_answer(old),
+                    describe_answer(new)
+                ));
+            }
+        }
+    }
+    Ok((doc, lines))
+}
+
+/// Merge an imported document into the state the same way a saved session
+/// is restored, then journal and snapshot the result.
+pub fn import_doc(state: &mut AppState, doc: &AnswersDoc) -> Result<(), String> {
+    restore_from_doc(state, doc);
+    let merged = submit::build_answers_doc(state);
+    state.journal.record(&state.state_dir, &merged)?;
+    save_state(state)
+}
+
+fn describe_answer(value: &AnswerValue) -> String {
+    let text = match value {
+        AnswerValue::Text(t) => t.replace('\n', " "),
+        AnswerValue::List(items) => items.join(", "),
+    };
+    if text.chars().count() > 40 {
+        let short: String = text.chars().take(37).collect();
+        format!("\"{}...\"", short)
+    } else {
+        format!("\"{}\"", text)
+    }
+}
+
 pub fn print_status(state: &AppState) {
     let counts = state.status_counts();
     let total = state.quiz.questions.len();
@@ -196,6 +510,120 @@ pub fn print_status(state: &AppState) {
     }
 }
 
+#[derive(Serialize)]
+struct StatusReport {
+    quiz: StatusQuiz,
+    repo: String,
+    state_dir: String,
+    window: StatusWindow,
+    remaining_seconds: Option<i64>,
+    started_at: Option<String>,
+    submission: StatusSubmission,
+    counts: StatusCounts,
+    questions: Vec<StatusQuestion>,
+}
+
+#[derive(Serialize)]
+struct StatusQuiz {
+    title: String,
+    file: String,
+    hash: String,
+}
+
+#[derive(Serialize)]
+struct StatusWindow {
+    start: String,
+    end: String,
+    /// `waiting`, `open`, `late` (past `end`, in the grace period of a
+    /// student who started) or `closed`.
+    state: &'static str,
+}
+
+#[derive(Serialize)]
+struct StatusSubmission {
+    /// `draft`, `written` (response/ exists but is not committed),
+    /// `committed` or `pushed`.
+    state: &'static str,
+    submitted_at: Option<String>,
+}
+
+#[derive(Serialize)]
+struct StatusQuestion {
+    number: u32,
+    id: String,
+    title: String,
+    #[serde(rename = "type")]
+    qtype: &'static str,
+    status: &'static str,
+}
+
+/// `--status --json`: progress, time window and submission state for
+/// scripts and shell prompts.
+pub fn status_json(state: &AppState) -> Result<String, String> {
+    let now = state.clock.now();
+    let fm = &state.quiz.frontmatter;
+    let window_state = if now < fm.start {
+        "waiting"
+    } else if now > fm.end && state.accepts_late_work() {
+        "late"
+    } else if now > fm.end {
+        "closed"
+    } else {
+        "open"
+    };
+    let remaining = fm.end.signed_duration_since(now).num_seconds().max(0);
+
+    let repo = &state.repo_dir;
+    let submission_state = if git::is_git_repo(repo) && git::is_response_pushed(repo) {
+        "pushed"
+    } else if git::is_git_repo(repo) && git::has_response_in_history(repo) {
+        "committed"
+    } else if git::has_response_in_worktree(repo) {
+        "written"
+    } else {
+        "draft"
+    };
+
+    let report = StatusReport {
+        quiz: StatusQuiz {
+            title: state.quiz.title.clone(),
+            file: state.quiz.quiz_file.clone(),
+            hash: state.quiz.quiz_hash.clone(),
+        },
+        repo: repo
+            .canonicalize()
+            .unwrap_or_else(|_| repo.clone())
+            .to_string_lossy()
+            .to_string(),
+        state_dir: state.state_dir.to_string_lossy().to_string(),
+        window: StatusWindow {
+            start: fm.start.to_rfc3339(),
+            end: fm.end.to_rfc3339(),
+            state: window_state,
+        },
+        remaining_seconds: (window_state == "open").then_some(remaining),
+        started_at: state.started_at.clone(),
+        submission: StatusSubmission {
+            state: submission_state,
+            submitted_at: state.submitted_at.clone(),
+        },
+        counts: state.status_counts(),
+        questions: state
+            .quiz
+            .questions
+            .iter()
+            .map(|q| StatusQuestion {
+                number: q.number,
+                id: q.key(),
+                title: q.title.clone(),
+                qtype: q.kind.type_name(),
+                status: state.question_status(q.number).name(),
+            })
+            .collect(),
+    };
+    serde_json::to_string_pretty(&report).map_err(|e| format!("Cannot serialize status: {}", e))
+}
+
 fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::ClockEvent;
+use crate::model::{AckData, HintReveal, SectionProgress};
+
+/// Version written to new answers.yaml files. Bump it together with a new
+/// step in `migrate` whenever the document shape changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The answers.yaml document: session state while working, and the
+/// submission itself once `response/` is committed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswersDoc {
+    pub schema_version: u32,
+    pub quiz: QuizMeta,
+    pub session: Session,
+    #[serde(default)]
+    pub questions: Vec<QuestionRecord>,
+    /// Clock discontinuities seen during the session; empty means none.
+    #[serde(default)]
+    pub clock_events: Vec<ClockEvent>,
+    /// When each section was entered and locked, for quizzes that have them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<SectionRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionRecord {
+    pub title: String,
+    #[serde(flatten)]
+    pub progress: SectionProgress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizMeta {
+    pub title: String,
+    pub source: String,
+    pub submitted_at: String,
+    pub duration: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub acknowledged: bool,
+    /// Instructor signature check at startup: `verified: <principal>` or
+    /// `unsigned`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// How long after `end` it was submitted (HH:MM:SS), during the `grace`
+    /// period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub late_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_points: Option<f64>,
+}
+
+/// Everything needed to resume after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub current_question: usize,
+    pub quiz_file_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgment: Option<AckData>,
+    /// Last journal entry included in this snapshot; later entries are
+    /// replayed on load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal_seq: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionRecord {
+    pub number: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub qtype: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    /// Choice label → text, kept so a later quiz edit can be detected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub hint_used: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub hints_used: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint_penalty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hint_reveals: Vec<HintReveal>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub done: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub flagged: bool,
+    /// Time on task: how long it was the current question, when it was
+    /// first opened and when its answer last changed.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub time_spent_seconds: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_visited_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_edited_at: Option<String>,
+    /// A label or text for single/short/long, a list for multi/file.
+    pub answer: Option<AnswerValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnswerValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl AnswerValue {
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            AnswerValue::Text(s) => Some(s),
+            AnswerValue::List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[String]> {
+        match self {
+            AnswerValue::List(v) => Some(v),
+            AnswerValue::Text(_) => None,
+        }
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn is_zero<T: Default + PartialEq>(n: &T) -> bool {
+    *n == T::default()
+}
+
+pub fn to_yaml(doc: &AnswersDoc) -> Result<String, String> {
+    serde_yaml::to_string(doc).map_err(|e| format!("Cannot serialize answers: {}", e))
+}
+
+/// Parse an answers.yaml of any known version, upgrading it to the current one.
+pub fn from_yaml(content: &str) -> Result<AnswersDoc, String> {
+    let mut value: serde_yaml::Value =
+        serde_yaml::from_str(content).map_err(|e| format!("Corrupt answers.yaml: {}", e))?;
+    if !value.is_mapping() {
+        return Err("Corrupt answers.yaml: not a mapping".to_string());
+    }
+
+    // Files written before the schema was versioned have no field: version 1
+    let version = value["schema_version"].as_u64().unwrap_or(1) as u32;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "answers.yaml has schema version {}, newer than this termquiz supports ({})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    for from in version..SCHEMA_VERSION {
+        migrate(&mut value, from);
+    }
+
+    serde_yaml::from_value(value).map_err(|e| format!("Corrupt answers.yaml: {}", e))
+}
+
+/// Upgrade a document from `from` to `from + 1` in place.
+fn migrate(value: &mut serde_yaml::Value, from: u32) {
+    if from == 1 {
+        migrate_v1(value);
+    }
+    value["schema_version"] = serde_yaml::Value::from(from + 1);
+}
+
+/// Version 1 was written by hand: long answers went through a `|` block,
+/// which added a trailing newline, and old files only had `hint_used`.
+fn migrate_v1(value: &mut serde_yaml::Value) {
+    let Some(questions) = value["questions"].as_sequence_mut() else {
+        return;
+    };
+    for q in questions {
+        if !q.is_mapping() {
+            continue;
+        }
+        if q["type"].as_str() == Some("long") {
+            if let Some(text) = q["answer"].as_str() {
+                let trimmed = text.strip_suffix('\n').unwrap_or(text).to_string();
+                q["answer"] = serde_yaml::Value::String(trimmed);
+            }
+        }
+        if q["hint_used"].as_bool() == Some(true) && q["hints_used"].is_null() {
+            q["hints_used"] = serde_yaml::Value::from(1);
+        }
+    }
+}
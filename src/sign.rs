@@ -0,0 +1,225 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// ssh-keygen signature namespace, so a termquiz signature cannot be passed
+/// off as a signature for anything else made with the same key.
+pub const NAMESPACE: &str = "termquiz";
+/// Namespace for the instructor's signature over a quiz file.
+pub const QUIZ_NAMESPACE: &str = "termquiz-quiz";
+
+fn run_ssh_keygen(args: &[&str], stdin: Option<&[u8]>) -> Result<String, String> {
+    // Never let ssh-keygen wait on a passphrase prompt inside the TUI
+    let mut child = Command::new("ssh-keygen")
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    if let (Some(data), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(data)
+            .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The student's signing key: `--sign-key`, else `$TERMQUIZ_SIGNING_KEY`.
+pub fn signing_key(cli: Option<&str>) -> Option<PathBuf> {
+    cli.map(PathBuf::from).or_else(|| {
+        std::env::var_os("TERMQUIZ_SIGNING_KEY")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Instructor keys trusted to sign quizzes, in allowed_signers format:
+/// `--trust`, else `$TERMQUIZ_TRUSTED_KEYS`.
+pub fn trusted_keys(cli: Option<&str>) -> Option<PathBuf> {
+    cli.map(PathBuf::from).or_else(|| {
+        std::env::var_os("TERMQUIZ_TRUSTED_KEYS")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// The instructor's detached signature for a quiz: `<quiz>.md.sig`.
+pub fn quiz_signature_path(quiz_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.sig", quiz_path.display()))
+}
+
+/// Sign a quiz's `content` with the instructor's `key`, writing the
+/// signature next to the quiz file.
+pub fn sign_quiz(key: &Path, quiz_path: &Path, content: &str) -> Result<PathBuf, String> {
+    let signature = sign_data(key, QUIZ_NAMESPACE, content.as_bytes())?;
+    let sig = quiz_signature_path(quiz_path);
+    fs::write(&sig, signature).map_err(|e| format!("Cannot write {}: {}", sig.display(), e))?;
+    Ok(sig)
+}
+
+/// Check the instructor's signature over the quiz `content` (includes
+/// resolved, so it covers every question and the time window). Returns the
+/// result to record in the submission: `verified: <principal>`, or
+/// `unsigned` when there is neither a signature nor trusted keys. A
+/// signature that cannot be checked for lack of trusted keys, a missing
+/// signature with trusted keys, or a bad one is an error.
+pub fn check_quiz(
+    quiz_path: &Path,
+    content: &str,
+    trusted: Option<&Path>,
+) -> Result<String, String> {
+    let sig = quiz_signature_path(quiz_path);
+    let Some(trusted) = trusted else {
+        if sig.exists() {
+            return Err(format!(
+                "{} is signed by the instructor, but no trusted keys are configured to check it (--trust or TERMQUIZ_TRUSTED_KEYS)",
+                quiz_path.display()
+            ));
+        }
+        return Ok("unsigned".to_string());
+    };
+    if !sig.exists() {
+        return Err(format!(
+            "{} is not signed by the instructor (no {})",
+            quiz_path.display(),
+            sig.display()
+        ));
+    }
+    let principal = verify_data(trusted, QUIZ_NAMESPACE, content.as_bytes(), &sig).map_err(|e| {
+        format!(
+            "{} does not match the instructor's signature; the questions or time window may have been changed: {}",
+            quiz_path.display(),
+            e
+        )
+    })?;
+    Ok(format!("verified: {}", principal))
+}
+
+/// Check before the TUI starts that `key` can sign without prompting: an
+/// unencrypted private key, or a `.pub` whose private half is in ssh-agent.
+pub fn check_key(key: &Path) -> Result<(), String> {
+    let key_str = key.to_string_lossy();
+    if !key.exists() {
+        return Err(format!("Signing key {} not found", key_str));
+    }
+    if key.extension().is_none_or(|e| e != "pub") {
+        run_ssh_keygen(&["-y", "-P", "", "-f", &key_str], None).map_err(|_| {
+            format!(
+                "Signing key {} is passphrase-protected; add it to ssh-agent and pass the .pub file instead",
+                key_str
+            )
+        })?;
+    }
+
+    let probe = std::env::temp_dir().join(format!("termquiz-sign-check-{}", std::process::id()));
+    fs::write(&probe, "termquiz")
+        .map_err(|e| format!("Cannot write {}: {}", probe.display(), e))?;
+    let result = sign_file(key, &probe);
+    let _ = fs::remove_file(&probe);
+    if let Ok(sig) = &result {
+        let _ = fs::remove_file(sig);
+    }
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Cannot sign with {}: {}", key_str, e))
+}
+
+/// Sign `file` with `key`, writing `<file>.sig`. Returns the signature path.
+pub fn sign_file(key: &Path, file: &Path) -> Result<PathBuf, String> {
+    let sig = PathBuf::from(format!("{}.sig", file.display()));
+    if sig.exists() {
+        fs::remove_file(&sig).map_err(|e| format!("Cannot replace {}: {}", sig.display(), e))?;
+    }
+    run_ssh_keygen(
+        &[
+            "-Y",
+            "sign",
+            "-f",
+            &key.to_string_lossy(),
+            "-n",
+            NAMESPACE,
+            &file.to_string_lossy(),
+        ],
+        None,
+    )?;
+    Ok(sig)
+}
+
+/// Sign `data` with `key` under `namespace`, returning the armored signature.
+pub fn sign_data(key: &Path, namespace: &str, data: &[u8]) -> Result<String, String> {
+    run_ssh_keygen(
+        &["-Y", "sign", "-f", &key.to_string_lossy(), "-n", namespace],
+        Some(data),
+    )
+}
+
+/// Check `sig` over `file` against a roster in OpenSSH allowed_signers
+/// format (`<principal> <key-type> <base64-key>` per line). Returns the
+/// principal whose key made the signature.
+pub fn verify_file(roster: &Path, file: &Path, sig: &Path) -> Result<String, String> {
+    let data = fs::read(file).map_err(|e| format!("Cannot read {}: {}", file.display(), e))?;
+    verify_data(roster, NAMESPACE, &data, sig)
+}
+
+/// Check `sig` over `data` under `namespace` against a roster, as
+/// [`verify_file`] does.
+pub fn verify_data(
+    roster: &Path,
+    namespace: &str,
+    data: &[u8],
+    sig: &Path,
+) -> Result<String, String> {
+    let roster_str = roster.to_string_lossy();
+    let sig_str = sig.to_string_lossy();
+    let principals = run_ssh_keygen(
+        &["-Y", "find-principals", "-s", &sig_str, "-f", &roster_str],
+        None,
+    )
+    .map_err(|_| {
+        format!(
+            "{} was made by a key that is not in the roster",
+            sig.display()
+        )
+    })?;
+    let principal = principals
+        .lines()
+        .next()
+        .map(|l| l.trim().to_string())
+        .ok_or_else(|| {
+            format!(
+                "{} was made by a key that is not in the roster",
+                sig.display()
+            )
+        })?;
+
+    run_ssh_keygen(
+        &[
+            "-Y",
+            "verify",
+            "-f",
+            &roster_str,
+            "-I",
+            &principal,
+            "-n",
+            namespace,
+            "-s",
+            &sig_str,
+        ],
+        Some(data),
+    )
+    .map_err(|e| format!("Bad signature {}: {}", sig.display(), e))?;
+    Ok(principal)
+}
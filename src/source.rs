@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::git;
+use crate::parser;
 
 pub fn is_git_url(s: &str) -> bool {
     s.starts_with("git@")
@@ -60,7 +61,7 @@ pub fn resolve_source(
             path
         };
 
-        if path.is_file() && path.extension().map_or(false, |e| e == "md") {
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
             let repo_dir = path
                 .parent()
                 .ok_or_else(|| "Cannot determine parent directory".to_string())?
@@ -75,6 +76,9 @@ pub fn resolve_source(
     }
 }
 
+/// Find the root quiz in a directory: the one `.md` file that starts with
+/// YAML frontmatter. Other `.md` files are question banks pulled in with
+/// `:::include`.
 fn find_quiz_file(dir: &Path) -> Result<PathBuf, String> {
     let mut md_files: Vec<PathBuf> = Vec::new();
 
@@ -84,26 +88,107 @@ fn find_quiz_file(dir: &Path) -> Result<PathBuf, String> {
     for entry in entries {
         let entry = entry.map_err(|e| format!("Error reading entry: {}", e))?;
         let path = entry.path();
-        if path.is_file() && path.extension().map_or(false, |e| e == "md") {
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
             md_files.push(path);
         }
     }
 
-    match md_files.len() {
+    if md_files.is_empty() {
+        return Err(format!("No .md quiz files found in {}", dir.display()));
+    }
+
+    md_files.sort();
+    let mut roots: Vec<PathBuf> = md_files
+        .into_iter()
+        .filter(|p| has_frontmatter(p))
+        .collect();
+
+    match roots.len() {
         0 => Err(format!(
-            "No .md quiz files found in {}",
+            "No quiz file with frontmatter (---) found in {}",
             dir.display()
         )),
-        1 => Ok(md_files.remove(0)),
+        1 => Ok(roots.remove(0)),
         _ => {
-            let names: Vec<String> = md_files
+            let names: Vec<String> = roots
                 .iter()
                 .map(|p| format!("  - {}", p.file_name().unwrap_or_default().to_string_lossy()))
                 .collect();
             Err(format!(
-                "Multiple .md files found. Specify which one:\n{}",
+                "Multiple quiz files with frontmatter found. Specify which one:\n{}",
                 names.join("\n")
             ))
         }
     }
 }
+
+fn has_frontmatter(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|content| parser::has_frontmatter(&content))
+}
+
+/// Read a quiz file and splice in every `:::include <path>` line, recursively.
+/// Include paths are relative to the file containing the directive. Lines
+/// inside fenced code blocks are left alone.
+pub fn read_quiz(path: &Path) -> Result<String, String> {
+    let mut stack: Vec<(PathBuf, PathBuf)> = Vec::new();
+    read_with_includes(path, &mut stack)
+}
+
+/// `stack` holds the files being read, canonical path first and the path as
+/// written second, so a cycle can be reported the way the author sees it.
+fn read_with_includes(path: &Path, stack: &mut Vec<(PathBuf, PathBuf)>) -> Result<String, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if let Some(start) = stack.iter().position(|(c, _)| *c == canonical) {
+        let chain: Vec<String> = stack[start..]
+            .iter()
+            .map(|(_, p)| p.display().to_string())
+            .chain(std::iter::once(path.display().to_string()))
+            .collect();
+        return Err(format!("Include cycle: {}", chain.join(" -> ")));
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+    if !stack.is_empty() && parser::has_frontmatter(&content) {
+        return Err(format!(
+            "Included file {} must not have frontmatter",
+            path.display()
+        ));
+    }
+
+    stack.push((canonical, path.to_path_buf()));
+    let base = path.parent().unwrap_or(Path::new("."));
+    let mut out = String::with_capacity(content.len());
+    let mut fence: Option<&str> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") {
+            fence = Some("```");
+        } else if trimmed.starts_with("~~~") {
+            fence = Some("~~~");
+        } else if let Some(target) = trimmed.strip_prefix(":::include") {
+            let target = target.trim();
+            if target.is_empty() {
+                return Err(format!("Empty :::include in {}", path.display()));
+            }
+            let included = read_with_includes(&base.join(target), stack)?;
+            out.push_str(&included);
+            if !included.ends_with('\n') {
+                out.push('\n');
+            }
+            continue;
+        }
+        out.push_str(line);
+    }
+
+    stack.pop();
+    Ok(out)
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
+use crate::clock::{Clock, ClockEvent};
 use crate::model::*;
+use crate::timer;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Screen {
@@ -13,6 +16,8 @@ pub enum Screen {
     Pushing,
     PushRetrying,
     SaveLocal,
+    /// Time ran out under `on_expire: lock`.
+    Locked,
     Done,
 }
 
@@ -22,8 +27,14 @@ pub enum Dialog {
     ConfirmQuit,
     ConfirmHint,
     DoneRequiresAnswer,
-    TwoMinuteWarning,
+    /// Countdown warning with this many seconds left.
+    TimeWarning(i64),
+    /// Moving on to the question at this index locks earlier sections.
+    ConfirmSection(usize),
+    /// The section at this index ran out of time and was locked.
+    SectionExpired(usize),
     Help,
+    QuizUpdated,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -56,6 +67,22 @@ pub enum MainFocus {
     FlagButton,
 }
 
+/// Time on task for one question, written into the submission.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuestionTiming {
+    /// Total time it was the current question while working.
+    pub focused: Duration,
+    pub first_visited_at: Option<String>,
+    pub last_edited_at: Option<String>,
+}
+
+/// A line in the sidebar list: a section heading or a question (by index).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SidebarRow {
+    Section(usize),
+    Question(usize),
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub screen: Screen,
@@ -65,6 +92,7 @@ pub struct AppState {
     pub flags: HashMap<u32, bool>,
     pub visited: HashMap<u32, bool>,
     pub hints_revealed: HashMap<u32, usize>,
+    pub hint_reveals: HashMap<u32, Vec<HintReveal>>,
     pub input_mode: InputMode,
     pub dialog_stack: Vec<Dialog>,
     pub choice_cursor: usize,
@@ -74,6 +102,8 @@ pub struct AppState {
     pub started_at: Option<String>,
     pub submitted_at: Option<String>,
     pub repo_dir: std::path::PathBuf,
+    /// Where drafts and staged files live until submit; see `persist::state_dir_for`.
+    pub state_dir: std::path::PathBuf,
     pub should_quit: bool,
     pub ack_data: Option<AckData>,
     pub ack_name: String,
@@ -91,10 +121,34 @@ pub struct AppState {
     pub dragging_scrollbar: bool,
     pub done_marks: HashMap<u32, bool>,
     pub status_filter: [bool; 5],
+    /// What happened to saved answers when the quiz changed between sessions.
+    pub migration_notes: Vec<String>,
+    /// Append-only log of answer changes; see `journal::record`.
+    pub journal: crate::journal::Journal,
+    /// Last error from the background writer, shown until a write succeeds.
+    pub save_error: Option<String>,
+    /// SSH key that signs the manifest and submit commit, if configured.
+    pub signing_key: Option<std::path::PathBuf>,
+    /// Result of checking the instructor's signature on the quiz, recorded
+    /// in the submission.
+    pub quiz_signature: Option<String>,
+    /// Clock discontinuities noticed so far, written into the submission.
+    pub clock_events: Vec<ClockEvent>,
+    /// Source of "now"; simulated in a rehearsal.
+    pub clock: Clock,
+    /// Flash the titlebar timer until then, for a `flash` warning.
+    pub flash_until: Option<Instant>,
+    pub timing: HashMap<u32, QuestionTiming>,
+    /// Start of the current question's focus interval; `None` while not
+    /// working. Monotonic, so clock changes cannot inflate it.
+    pub focus_since: Option<Instant>,
+    /// Progress through each of `quiz.sections`.
+    pub sections: Vec<SectionProgress>,
 }
 
 impl AppState {
     pub fn new(quiz: Quiz, repo_dir: std::path::PathBuf) -> Self {
+        let sections = vec![SectionProgress::default(); quiz.sections.len()];
         Self {
             screen: Screen::Working,
             quiz,
@@ -103,6 +157,7 @@ impl AppState {
             flags: HashMap::new(),
             visited: HashMap::new(),
             hints_revealed: HashMap::new(),
+            hint_reveals: HashMap::new(),
             input_mode: InputMode::Navigation,
             dialog_stack: Vec::new(),
             choice_cursor: 0,
@@ -111,6 +166,7 @@ impl AppState {
             remaining_seconds: None,
             started_at: None,
             submitted_at: None,
+            state_dir: crate::persist::state_dir_for(&repo_dir),
             repo_dir,
             should_quit: false,
             ack_data: None,
@@ -129,6 +185,64 @@ impl AppState {
             dragging_scrollbar: false,
             done_marks: HashMap::new(),
             status_filter: [true; 5],
+            migration_notes: Vec::new(),
+            journal: crate::journal::Journal::default(),
+            save_error: None,
+            signing_key: None,
+            quiz_signature: None,
+            clock_events: Vec::new(),
+            clock: Clock::system(),
+            flash_until: None,
+            timing: HashMap::new(),
+            focus_since: None,
+            sections,
+        }
+    }
+
+    /// Whether this is a `--simulate-time`/`--speed` rehearsal, which never
+    /// touches the real draft or the repo.
+    pub fn is_rehearsal(&self) -> bool {
+        self.clock.is_simulated()
+    }
+
+    /// Where `response/` is written: the repo, or a rehearsal's own
+    /// directory.
+    pub fn submit_dir(&self) -> std::path::PathBuf {
+        if self.is_rehearsal() {
+            self.state_dir.join("submitted")
+        } else {
+            self.repo_dir.clone()
+        }
+    }
+
+    /// Past `end`, a student who already started may keep going until the
+    /// grace period ends. Nobody starts late.
+    pub fn accepts_late_work(&self) -> bool {
+        let fm = &self.quiz.frontmatter;
+        self.started_at.is_some() && self.clock.now() <= fm.grace_end()
+    }
+
+    /// The screen to open on for the time window as of now: resume straight
+    /// into the quiz if it was already started.
+    pub fn initial_screen(&self) -> Screen {
+        let now = self.clock.now();
+        if now < self.quiz.frontmatter.start {
+            return Screen::Waiting;
+        }
+        if now > self.quiz.frontmatter.end && !self.accepts_late_work() {
+            return Screen::Closed;
+        }
+        let needs_ack = self
+            .quiz
+            .frontmatter
+            .acknowledgment
+            .as_ref()
+            .map(|a| a.required)
+            .unwrap_or(false);
+        if self.started_at.is_some() && (!needs_ack || self.ack_data.is_some()) {
+            Screen::Working
+        } else {
+            Screen::Preamble
         }
     }
 
@@ -142,11 +256,21 @@ impl AppState {
             .unwrap_or(0)
     }
 
+    /// Persistence key for a question number; see `Question::key`.
+    pub fn question_key(&self, qnum: u32) -> String {
+        self.quiz
+            .questions
+            .iter()
+            .find(|q| q.number == qnum)
+            .map(|q| q.key())
+            .unwrap_or_else(|| format!("q{}", qnum))
+    }
+
     pub fn question_status(&self, qnum: u32) -> QuestionStatus {
         // For the current Short/Long question, use live text_input length
         let is_current_text = self.current_question()
             .filter(|q| q.number == qnum)
-            .map_or(false, |q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
+            .is_some_and(|q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
         let current_text_empty = is_current_text && self.text_input.is_empty();
 
         // Done is invalid when the current text field is empty
@@ -183,6 +307,63 @@ impl AppState {
         counts
     }
 
+    /// Unread, empty, or flagged without an answer.
+    pub fn is_unanswered(&self, qnum: u32) -> bool {
+        match self.question_status(qnum) {
+            QuestionStatus::Unread | QuestionStatus::NotAnswered => true,
+            QuestionStatus::Flagged => !self.answers.contains_key(&qnum),
+            QuestionStatus::Answered | QuestionStatus::Done => false,
+        }
+    }
+
+    /// Points still on the table, for the submit dialog.
+    pub fn unanswered_points(&self) -> f64 {
+        self.quiz
+            .questions
+            .iter()
+            .filter(|q| self.is_unanswered(q.number))
+            .filter_map(|q| q.points)
+            .sum()
+    }
+
+    /// Seconds ahead of (positive) or behind (negative) pace, when questions
+    /// declare weights. With `minutes`, pace is the time left minus the
+    /// estimate for unanswered questions. With only `points`, it is the time
+    /// left minus the unanswered share of the points, applied to the time
+    /// from starting to `end`.
+    pub fn pace_seconds(&self) -> Option<i64> {
+        let remaining = self.remaining_seconds.filter(|s| *s > 0)?;
+        let questions = &self.quiz.questions;
+        let unanswered = |q: &&Question| self.is_unanswered(q.number);
+
+        if questions.iter().any(|q| q.minutes.is_some()) {
+            let needed: f64 = questions
+                .iter()
+                .filter(unanswered)
+                .filter_map(|q| q.minutes)
+                .sum();
+            return Some(remaining - (needed * 60.0).round() as i64);
+        }
+
+        let total: f64 = questions.iter().filter_map(|q| q.points).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let left: f64 = questions
+            .iter()
+            .filter(unanswered)
+            .filter_map(|q| q.points)
+            .sum();
+        let fm = &self.quiz.frontmatter;
+        let began = self
+            .started_at
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .unwrap_or(fm.start);
+        let budget = (fm.end - began).num_seconds().max(0) as f64;
+        Some(remaining - (budget * left / total).round() as i64)
+    }
+
     /// Toggle done mark. Returns false if marking done but no answer exists.
     pub fn toggle_done(&mut self) -> bool {
         let qnum = self.current_question_number();
@@ -194,7 +375,7 @@ impl AppState {
             // For current Short/Long, check live text_input instead of answers map
             let has_answer = {
                 let is_current_text = self.current_question()
-                    .map_or(false, |q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
+                    .is_some_and(|q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
                 if is_current_text {
                     !self.text_input.is_empty()
                 } else {
@@ -232,7 +413,7 @@ impl AppState {
         // For the current Short/Long question, done is invalid when text is empty
         let is_current_text = self.current_question()
             .filter(|q| q.number == qnum)
-            .map_or(false, |q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
+            .is_some_and(|q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
         if is_current_text && self.text_input.is_empty() {
             return false;
         }
@@ -270,13 +451,32 @@ impl AppState {
             .collect()
     }
 
+    /// Go to a question, asking first if that means leaving the current
+    /// section behind.
     pub fn navigate_to(&mut self, idx: usize) {
+        let target = self.quiz.questions.get(idx).and_then(|q| q.section);
+        if let (Some(target), Some(active)) = (target, self.active_section()) {
+            if target > active {
+                self.push_dialog(Dialog::ConfirmSection(idx));
+                return;
+            }
+        }
+        self.go_to(idx);
+    }
+
+    /// Go to a question without any section checks.
+    pub fn go_to(&mut self, idx: usize) {
         if idx < self.quiz.questions.len() {
             // Save current text input
             self.save_current_text_input();
+            let focused = self.focus_since.is_some();
+            self.end_focus();
             self.current_question = idx;
             let qnum = self.quiz.questions[idx].number;
             self.visited.insert(qnum, true);
+            if focused {
+                self.begin_focus();
+            }
             // Load answer text if exists
             self.load_text_input_for_current();
             self.choice_cursor = 0;
@@ -287,11 +487,160 @@ impl AppState {
         }
     }
 
+    /// The section the student is working in: the first one not yet locked.
+    pub fn active_section(&self) -> Option<usize> {
+        self.sections.iter().position(|s| s.locked_at.is_none())
+    }
+
+    pub fn is_locked(&self, qnum: u32) -> bool {
+        self.quiz
+            .questions
+            .iter()
+            .find(|q| q.number == qnum)
+            .and_then(|q| q.section)
+            .and_then(|s| self.sections.get(s))
+            .is_some_and(|s| s.locked_at.is_some())
+    }
+
+    pub fn current_is_locked(&self) -> bool {
+        self.is_locked(self.current_question_number())
+    }
+
+    pub fn start_section(&mut self, section: usize) {
+        let now = self.clock.now().to_rfc3339();
+        if let Some(progress) = self.sections.get_mut(section) {
+            progress.started_at.get_or_insert(now);
+        }
+    }
+
+    /// Move on to the question at `idx`, locking every section before its own.
+    pub fn advance_to(&mut self, idx: usize) {
+        let Some(target) = self.quiz.questions.get(idx).and_then(|q| q.section) else {
+            return;
+        };
+        self.save_current_text_input();
+        self.lock_sections(target);
+        self.go_to(idx);
+        self.start_section(target);
+    }
+
+    fn lock_sections(&mut self, before: usize) {
+        let now = self.clock.now().to_rfc3339();
+        for progress in self.sections.iter_mut().take(before) {
+            progress.locked_at.get_or_insert_with(|| now.clone());
+        }
+    }
+
+    /// Seconds left in the active section, if it has a time limit. Counts
+    /// from when the student entered it, or from now if they have not yet.
+    pub fn section_remaining(&self) -> Option<(usize, i64)> {
+        let active = self.active_section()?;
+        let limit = self.quiz.sections[active].time_limit?;
+        let remaining = match &self.sections[active].started_at {
+            Some(started) => timer::section_remaining(started, limit, &self.clock)?,
+            None => limit,
+        };
+        Some((active, remaining))
+    }
+
+    /// Start the active section and lock it if its time is up, moving the
+    /// student on to the next one. Returns the section that was locked.
+    pub fn check_section_time(&mut self) -> Option<usize> {
+        let active = self.active_section()?;
+        self.start_section(active);
+        let (_, remaining) = self.section_remaining()?;
+        if remaining > 0 {
+            return None;
+        }
+        self.save_current_text_input();
+        self.lock_sections(active + 1);
+        match self
+            .quiz
+            .questions
+            .iter()
+            .position(|q| q.section == Some(active + 1))
+        {
+            Some(idx) => {
+                self.go_to(idx);
+                self.start_section(active + 1);
+            }
+            None => self.update_input_mode(),
+        }
+        Some(active)
+    }
+
+    /// What the sidebar lists: the visible questions, each run of them
+    /// headed by its section.
+    pub fn sidebar_rows(&self) -> Vec<SidebarRow> {
+        let mut rows = Vec::new();
+        let mut last = None;
+        for idx in self.filtered_questions() {
+            let section = self.quiz.questions[idx].section;
+            if let Some(s) = section.filter(|_| section != last) {
+                rows.push(SidebarRow::Section(s));
+            }
+            last = section;
+            rows.push(SidebarRow::Question(idx));
+        }
+        rows
+    }
+
+    /// Start or stop timing the current question as the student enters or
+    /// leaves the working screen.
+    pub fn track_focus(&mut self, working: bool) {
+        if working {
+            self.begin_focus();
+        } else {
+            self.end_focus();
+        }
+    }
+
+    fn begin_focus(&mut self) {
+        if self.focus_since.is_some() {
+            return;
+        }
+        let Some(qnum) = self.current_question().map(|q| q.number) else {
+            return;
+        };
+        self.focus_since = Some(Instant::now());
+        let now = self.clock.now().to_rfc3339();
+        let timing = self.timing.entry(qnum).or_default();
+        timing.first_visited_at.get_or_insert(now);
+    }
+
+    fn end_focus(&mut self) {
+        let Some(since) = self.focus_since.take() else {
+            return;
+        };
+        let qnum = self.current_question_number();
+        self.timing.entry(qnum).or_default().focused += since.elapsed();
+    }
+
+    /// Time spent on a question so far, including the current interval.
+    pub fn time_on_question(&self, qnum: u32) -> Duration {
+        let saved = self
+            .timing
+            .get(&qnum)
+            .map(|t| t.focused)
+            .unwrap_or_default();
+        match self.focus_since {
+            Some(since) if self.current_question_number() == qnum => saved + since.elapsed(),
+            _ => saved,
+        }
+    }
+
+    /// Record that `qnum`'s answer changed just now. Called by every path
+    /// that edits an answer: typing, choosing, attaching and the editor.
+    pub fn mark_edited(&mut self, qnum: u32) {
+        let now = self.clock.now().to_rfc3339();
+        self.timing.entry(qnum).or_default().last_edited_at = Some(now);
+    }
+
     pub fn cycle_main_focus(&mut self) {
-        let has_unrevealed_hints = self.current_question().map_or(false, |q| {
+        let has_unrevealed_hints = self.current_question().is_some_and(|q| {
             let qnum = q.number;
             let revealed = self.hints_revealed.get(&qnum).copied().unwrap_or(0);
-            q.hints.len() > 0 && revealed < q.hints.len()
+            !q.hints.is_empty() && revealed < q.hints.len()
         });
 
         self.main_focus = match self.main_focus {
@@ -316,7 +665,43 @@ impl AppState {
         };
     }
 
+    /// The next unrevealed hint for the current question, if any.
+    pub fn next_hint(&self) -> Option<&Hint> {
+        if self.current_is_locked() {
+            return None;
+        }
+        let q = self.current_question()?;
+        let revealed = self.hints_revealed.get(&q.number).copied().unwrap_or(0);
+        q.hints.get(revealed)
+    }
+
+    /// Reveal the next hint for the current question and record it in the audit trail.
+    pub fn reveal_next_hint(&mut self) {
+        let Some(cost) = self.next_hint().map(|h| h.cost) else {
+            return;
+        };
+        let qnum = self.current_question_number();
+        let level = self.hints_revealed.get(&qnum).copied().unwrap_or(0) + 1;
+        self.hints_revealed.insert(qnum, level);
+        self.hint_reveals.entry(qnum).or_default().push(HintReveal {
+            level,
+            revealed_at: self.clock.now().to_rfc3339(),
+            cost,
+        });
+    }
+
+    /// Total points deducted for hints revealed on a question.
+    pub fn hint_penalty(&self, qnum: u32) -> f64 {
+        self.hint_reveals
+            .get(&qnum)
+            .map(|reveals| reveals.iter().filter_map(|r| r.cost).sum())
+            .unwrap_or(0.0)
+    }
+
     pub fn save_current_text_input(&mut self) {
+        if self.current_is_locked() {
+            return;
+        }
         if let Some(q) = self.current_question().cloned() {
             match &q.kind {
                 QuestionKind::Short => {
@@ -371,7 +756,11 @@ impl AppState {
         self.text_cursor = 0;
     }
 
-    fn update_input_mode(&mut self) {
+    pub fn update_input_mode(&mut self) {
+        if self.current_is_locked() {
+            self.input_mode = InputMode::Navigation;
+            return;
+        }
         if let Some(q) = self.current_question() {
             match &q.kind {
                 QuestionKind::SingleChoice(_) | QuestionKind::MultiChoice(_) => {
@@ -391,25 +780,32 @@ impl AppState {
     }
 
     pub fn select_single_choice(&mut self, idx: usize) {
+        if self.current_is_locked() {
+            return;
+        }
         if let Some(q) = self.current_question().cloned() {
             if let QuestionKind::SingleChoice(choices) = &q.kind {
                 if idx < choices.len() {
                     let label = choices[idx].label.to_string();
-                    self.answers.insert(
-                        q.number,
-                        Answer {
-                            answer_type: "single".to_string(),
-                            selected: Some(vec![label]),
-                            text: None,
-                            files: None,
-                        },
-                    );
+                    let answer = Answer {
+                        answer_type: "single".to_string(),
+                        selected: Some(vec![label]),
+                        text: None,
+                        files: None,
+                    };
+                    if self.answers.get(&q.number) != Some(&answer) {
+                        self.answers.insert(q.number, answer);
+                        self.mark_edited(q.number);
+                    }
                 }
             }
         }
     }
 
     pub fn toggle_multi_choice(&mut self, idx: usize) {
+        if self.current_is_locked() {
+            return;
+        }
         if let Some(q) = self.current_question().cloned() {
             if let QuestionKind::MultiChoice(choices) = &q.kind {
                 if idx < choices.len() {
@@ -435,6 +831,7 @@ impl AppState {
                             files: None,
                         },
                     );
+                    self.mark_edited(q.number);
                 }
             }
         }
@@ -459,6 +856,9 @@ impl AppState {
     }
 
     pub fn add_file(&mut self, qnum: u32, file_path: String) {
+        if self.is_locked(qnum) {
+            return;
+        }
         let existing = self.answers.entry(qnum).or_insert_with(|| Answer {
             answer_type: "file".to_string(),
             selected: None,
@@ -468,6 +868,7 @@ impl AppState {
         if let Some(files) = &mut existing.files {
             files.push(file_path);
         }
+        self.mark_edited(qnum);
     }
 
     pub fn has_dialog(&self) -> bool {
@@ -496,7 +897,20 @@ pub enum QuestionStatus {
     Flagged,
 }
 
-#[derive(Debug, Default)]
+impl QuestionStatus {
+    /// Name used in exports and machine-readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuestionStatus::Unread => "unread",
+            QuestionStatus::NotAnswered => "not_answered",
+            QuestionStatus::Answered => "answered",
+            QuestionStatus::Done => "done",
+            QuestionStatus::Flagged => "flagged",
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize)]
 pub struct StatusCounts {
     pub unread: usize,
     pub not_answered: usize,
@@ -1,21 +1,55 @@
 use std::fs;
 use std::path::Path;
 
-use crate::model::QuestionKind;
+use crate::encrypt;
+use crate::manifest::{self, Manifest};
+use crate::model::{Question, QuestionKind};
+use crate::schema::{
+    self, AnswerValue, AnswersDoc, QuestionRecord, QuizMeta, SectionRecord, Session, SCHEMA_VERSION,
+};
 use crate::state::AppState;
 
-/// Build response directory: copy file attachments.
-/// The answers.yaml is already written by persist::save_state.
-pub fn build_response(state: &AppState, repo_dir: &Path) -> Result<(), String> {
+/// Materialize `response/` in the repo: answers.yaml, the staged file
+/// attachments and a manifest hashing them. Until submit, everything lives
+/// in the state directory.
+///
+/// With a `recipient`, the tree is built in the state directory instead and
+/// only its encrypted archive, plus a manifest of that, goes into the repo.
+/// Either way the returned manifest is the one over the plaintext answers.
+pub fn build_response(state: &AppState, repo_dir: &Path) -> Result<Manifest, String> {
     let response_dir = repo_dir.join("response");
-    fs::create_dir_all(&response_dir)
-        .map_err(|e| format!("Cannot create response dir: {}", e))?;
+    let Some(recipient) = &state.quiz.frontmatter.recipient else {
+        return write_tree(state, &response_dir);
+    };
+
+    let staging = state.state_dir.join("response");
+    let _ = fs::remove_dir_all(&staging);
+    let manifest = write_tree(state, &staging)?;
+    let _ = fs::remove_dir_all(&response_dir);
+    fs::create_dir_all(&response_dir).map_err(|e| format!("Cannot create response dir: {}", e))?;
+    let sealed = encrypt::encrypt_dir(
+        &staging,
+        recipient,
+        &response_dir.join(encrypt::ARCHIVE_FILE),
+    );
+    let _ = fs::remove_dir_all(&staging);
+    sealed?;
+    manifest::write_manifest(state, &response_dir)?;
+    Ok(manifest)
+}
+
+fn write_tree(state: &AppState, response_dir: &Path) -> Result<Manifest, String> {
+    fs::create_dir_all(response_dir).map_err(|e| format!("Cannot create response dir: {}", e))?;
+
+    let yaml = build_answers_yaml(state)?;
+    fs::write(response_dir.join("answers.yaml"), yaml)
+        .map_err(|e| format!("Cannot write answers.yaml: {}", e))?;
 
     // Copy file attachments
     let files_dir = response_dir.join("files");
     for (qnum, answer) in &state.answers {
         if let Some(file_list) = &answer.files {
-            let q_dir = files_dir.join(format!("q{}", qnum));
+            let q_dir = files_dir.join(state.question_key(*qnum));
             fs::create_dir_all(&q_dir)
                 .map_err(|e| format!("Cannot create files dir: {}", e))?;
 
@@ -33,170 +67,138 @@ pub fn build_response(state: &AppState, repo_dir: &Path) -> Result<(), String> {
         }
     }
 
-    Ok(())
+    manifest::write_manifest(state, response_dir)
 }
 
-pub fn build_answers_yaml(state: &AppState) -> String {
-    let mut out = String::new();
-
-    // quiz metadata
-    out.push_str("quiz:\n");
-    out.push_str(&format!("  title: {:?}\n", state.quiz.title));
-    out.push_str(&format!("  source: {:?}\n", state.quiz.quiz_file));
-    out.push_str(&format!(
-        "  submitted_at: {:?}\n",
-        state.submitted_at.as_deref().unwrap_or("unknown")
-    ));
-    out.push_str(&format!(
-        "  duration: {:?}\n",
-        compute_duration(&state.started_at, &state.submitted_at)
-    ));
-    if state.ack_data.is_some() {
-        out.push_str("  acknowledged: true\n");
-    }
+pub fn build_answers_yaml(state: &AppState) -> Result<String, String> {
+    schema::to_yaml(&build_answers_doc(state))
+}
+
+pub fn build_answers_doc(state: &AppState) -> AnswersDoc {
+    let quiz = QuizMeta {
+        title: state.quiz.title.clone(),
+        source: state.quiz.quiz_file.clone(),
+        submitted_at: state
+            .submitted_at
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+        duration: compute_duration(&state.started_at, &state.submitted_at),
+        acknowledged: state.ack_data.is_some(),
+        signature: state.quiz_signature.clone(),
+        late_by: compute_late_by(state),
+        total_points: state.quiz.total_points(),
+    };
 
     // session state (for restore on restart)
-    out.push_str("\nsession:\n");
-    out.push_str(&format!("  current_question: {}\n", state.current_question));
-    out.push_str(&format!("  quiz_file_hash: {:?}\n", state.quiz.quiz_hash));
-    if let Some(ref started) = state.started_at {
-        out.push_str(&format!("  started_at: {:?}\n", started));
-    }
-    if let Some(ref ack) = state.ack_data {
-        out.push_str("  acknowledgment:\n");
-        out.push_str(&format!("    name: {:?}\n", ack.name));
-        out.push_str(&format!("    agreed_at: {:?}\n", ack.agreed_at));
-        out.push_str(&format!("    text_hash: {:?}\n", ack.text_hash));
+    let session = Session {
+        current_question: state.current_question,
+        quiz_file_hash: state.quiz.quiz_hash.clone(),
+        started_at: state.started_at.clone(),
+        acknowledgment: state.ack_data.clone(),
+        journal_seq: (state.journal.seq > 0).then_some(state.journal.seq),
+    };
+
+    let questions = state
+        .quiz
+        .questions
+        .iter()
+        .map(|q| question_record(state, q))
+        .collect();
+
+    AnswersDoc {
+        schema_version: SCHEMA_VERSION,
+        quiz,
+        session,
+        questions,
+        clock_events: state.clock_events.clone(),
+        sections: state
+            .quiz
+            .sections
+            .iter()
+            .zip(&state.sections)
+            .map(|(section, progress)| SectionRecord {
+                title: section.title.clone(),
+                progress: progress.clone(),
+            })
+            .collect(),
     }
+}
 
-    // questions
-    out.push_str("\nquestions:\n");
-    for q in &state.quiz.questions {
-        out.push_str(&format!("  - number: {}\n", q.number));
-        out.push_str(&format!("    title: {:?}\n", q.title));
-
-        let answer = state.answers.get(&q.number);
-        let hint_used = state.hints_revealed.get(&q.number).copied().unwrap_or(0) > 0;
-        let done = state.done_marks.get(&q.number).copied().unwrap_or(false);
-        let flagged = state.flags.get(&q.number).copied().unwrap_or(false);
-
-        match &q.kind {
-            QuestionKind::SingleChoice(choices) => {
-                out.push_str("    type: single\n");
-                out.push_str("    choices:\n");
-                for c in choices {
-                    out.push_str(&format!("      {}: {:?}\n", c.label, c.text));
-                }
-                if hint_used {
-                    out.push_str("    hint_used: true\n");
-                }
-                if done {
-                    out.push_str("    done: true\n");
-                }
-                if flagged {
-                    out.push_str("    flagged: true\n");
-                }
-                match answer.and_then(|a| a.selected.as_ref()) {
-                    Some(sel) if !sel.is_empty() => {
-                        out.push_str(&format!("    answer: {}\n", sel[0]));
-                    }
-                    _ => out.push_str("    answer: null\n"),
-                }
-            }
-            QuestionKind::MultiChoice(choices) => {
-                out.push_str("    type: multi\n");
-                out.push_str("    choices:\n");
-                for c in choices {
-                    out.push_str(&format!("      {}: {:?}\n", c.label, c.text));
-                }
-                if hint_used {
-                    out.push_str("    hint_used: true\n");
-                }
-                if done {
-                    out.push_str("    done: true\n");
-                }
-                if flagged {
-                    out.push_str("    flagged: true\n");
-                }
-                match answer.and_then(|a| a.selected.as_ref()) {
-                    Some(sel) if !sel.is_empty() => {
-                        let labels: Vec<&str> = sel.iter().map(|s| s.as_str()).collect();
-                        out.push_str(&format!("    answer: [{}]\n", labels.join(", ")));
-                    }
-                    _ => out.push_str("    answer: null\n"),
-                }
-            }
-            QuestionKind::Short => {
-                out.push_str("    type: short\n");
-                if hint_used {
-                    out.push_str("    hint_used: true\n");
-                }
-                if done {
-                    out.push_str("    done: true\n");
-                }
-                if flagged {
-                    out.push_str("    flagged: true\n");
-                }
-                match answer.and_then(|a| a.text.as_ref()) {
-                    Some(text) => {
-                        out.push_str(&format!("    answer: {:?}\n", text));
-                    }
-                    None => out.push_str("    answer: null\n"),
-                }
-            }
-            QuestionKind::Long => {
-                out.push_str("    type: long\n");
-                if hint_used {
-                    out.push_str("    hint_used: true\n");
-                }
-                if done {
-                    out.push_str("    done: true\n");
-                }
-                if flagged {
-                    out.push_str("    flagged: true\n");
-                }
-                match answer.and_then(|a| a.text.as_ref()) {
-                    Some(text) => {
-                        out.push_str("    answer: |\n");
-                        for line in text.lines() {
-                            out.push_str(&format!("      {}\n", line));
-                        }
-                    }
-                    None => out.push_str("    answer: null\n"),
-                }
-            }
-            QuestionKind::File(_) => {
-                out.push_str("    type: file\n");
-                if hint_used {
-                    out.push_str("    hint_used: true\n");
-                }
-                if done {
-                    out.push_str("    done: true\n");
-                }
-                if flagged {
-                    out.push_str("    flagged: true\n");
-                }
-                match answer.and_then(|a| a.files.as_ref()) {
-                    Some(files) if !files.is_empty() => {
-                        out.push_str("    answer:\n");
-                        for f in files {
+fn question_record(state: &AppState, q: &Question) -> QuestionRecord {
+    let answer = state.answers.get(&q.number);
+
+    let choices = match &q.kind {
+        QuestionKind::SingleChoice(choices) | QuestionKind::MultiChoice(choices) => Some(
+            choices
+                .iter()
+                .map(|c| (c.label.to_string(), c.text.clone()))
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    let answer = match &q.kind {
+        QuestionKind::SingleChoice(_) => answer
+            .and_then(|a| a.selected.as_ref())
+            .and_then(|sel| sel.first())
+            .map(|label| AnswerValue::Text(label.clone())),
+        QuestionKind::MultiChoice(_) => answer
+            .and_then(|a| a.selected.as_ref())
+            .filter(|sel| !sel.is_empty())
+            .map(|sel| AnswerValue::List(sel.clone())),
+        QuestionKind::Short | QuestionKind::Long => answer
+            .and_then(|a| a.text.as_ref())
+            .map(|text| AnswerValue::Text(text.clone())),
+        QuestionKind::File(_) => answer
+            .and_then(|a| a.files.as_ref())
+            .filter(|files| !files.is_empty())
+            .map(|files| {
+                AnswerValue::List(
+                    files
+                        .iter()
+                        .map(|f| {
                             let filename = Path::new(f)
                                 .file_name()
                                 .map(|n| n.to_string_lossy().to_string())
                                 .unwrap_or_else(|| f.clone());
-                            out.push_str(&format!(
-                                "      - files/q{}/{}\n",
-                                q.number, filename
-                            ));
-                        }
-                    }
-                    _ => out.push_str("    answer: null\n"),
-                }
-            }
-        }
-    }
+                            format!("files/{}/{}", q.key(), filename)
+                        })
+                        .collect(),
+                )
+            }),
+    };
 
-    out
+    // Hint audit trail: how many levels were opened, when, and at what cost
+    let hints_used = state.hints_revealed.get(&q.number).copied().unwrap_or(0);
+    let penalty = state.hint_penalty(q.number);
+    let timing = state.timing.get(&q.number);
+
+    QuestionRecord {
+        number: q.number,
+        id: Some(q.key()),
+        title: q.title.clone(),
+        qtype: q.kind.type_name().to_string(),
+        points: q.points,
+        choices,
+        hint_used: hints_used > 0,
+        hints_used,
+        hint_penalty: (penalty > 0.0).then_some(penalty),
+        hint_reveals: if hints_used > 0 {
+            state
+                .hint_reveals
+                .get(&q.number)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        },
+        done: state.done_marks.get(&q.number).copied().unwrap_or(false),
+        flagged: state.flags.get(&q.number).copied().unwrap_or(false),
+        time_spent_seconds: state.time_on_question(q.number).as_secs(),
+        first_visited_at: timing.and_then(|t| t.first_visited_at.clone()),
+        last_edited_at: timing.and_then(|t| t.last_edited_at.clone()),
+        answer,
+    }
 }
 
 fn compute_duration(started: &Option<String>, submitted: &Option<String>) -> String {
@@ -205,16 +207,25 @@ fn compute_duration(started: &Option<String>, submitted: &Option<String>) -> Str
             chrono::DateTime::parse_from_rfc3339(s),
             chrono::DateTime::parse_from_rfc3339(e),
         ) {
-            let secs = (end - start).num_seconds().max(0);
-            let h = secs / 3600;
-            let m = (secs % 3600) / 60;
-            let s = secs % 60;
-            return format!("{:02}:{:02}:{:02}", h, m, s);
+            return format_hms((end - start).num_seconds().max(0));
         }
     }
     "unknown".to_string()
 }
 
+fn compute_late_by(state: &AppState) -> Option<String> {
+    let submitted = chrono::DateTime::parse_from_rfc3339(state.submitted_at.as_deref()?).ok()?;
+    let late = (submitted - state.quiz.frontmatter.end).num_seconds();
+    (late > 0).then(|| format_hms(late))
+}
+
+fn format_hms(secs: i64) -> String {
+    let h = secs / 3600;
+    let m = (secs % 3600) / 60;
+    let s = secs % 60;
+    format!("{:02}:{:02}:{:02}", h, m, s)
+}
+
 pub fn build_commit_message(state: &AppState) -> String {
     let counts = state.status_counts();
     let total = state.quiz.questions.len();
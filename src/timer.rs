@@ -1,38 +1,70 @@
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, FixedOffset, Utc};
 
+use crate::clock::{Clock, ClockEvent, ClockMonitor};
+use crate::model::Warning;
+
 #[derive(Debug, Clone)]
 pub enum TimerEvent {
+    /// Seconds until `end`; negative once past it.
     Tick(i64),
-    TwoMinuteWarning,
+    Warning(Warning),
     TimeExpired,
+    /// The frontmatter `grace` period after `end` is over.
+    GraceExpired,
+    ClockJump(ClockEvent),
 }
 
+/// Tick every second until the receiver is dropped. Each warning fires once
+/// when its time is reached; if several are passed at once (e.g. starting
+/// late) only the most urgent is shown. With a `grace` of more than zero
+/// seconds, `GraceExpired` follows that long after `TimeExpired`.
 pub fn spawn_timer(
     end_time: DateTime<FixedOffset>,
+    grace: i64,
+    clock: Clock,
+    warnings: Vec<Warning>,
 ) -> mpsc::Receiver<TimerEvent> {
     let (tx, rx) = mpsc::channel();
 
     thread::spawn(move || {
-        let mut warned_two_min = false;
-        let expired = false;
+        let mut pending = warnings;
+        let mut expired = false;
+        let mut grace_expired = false;
+        // Only the system clock can be tampered with
+        let mut monitor =
+            (!clock.is_simulated()).then(|| ClockMonitor::new(Utc::now(), Instant::now()));
 
         loop {
-            let now = Utc::now();
+            let now = clock.now();
+            if let Some(jump) = monitor.as_mut().and_then(|m| m.check(now, Instant::now())) {
+                let _ = tx.send(TimerEvent::ClockJump(jump));
+            }
             let remaining = end_time.signed_duration_since(now);
             let secs = remaining.num_seconds();
 
             if secs <= 0 && !expired {
+                expired = true;
                 let _ = tx.send(TimerEvent::TimeExpired);
-                break;
+            }
+            if grace > 0 && secs <= -grace && !grace_expired {
+                grace_expired = true;
+                let _ = tx.send(TimerEvent::GraceExpired);
             }
 
-            if secs <= 120 && secs > 0 && !warned_two_min {
-                warned_two_min = true;
-                let _ = tx.send(TimerEvent::TwoMinuteWarning);
+            if secs > 0 {
+                let due = pending
+                    .iter()
+                    .filter(|w| secs <= w.secs)
+                    .min_by_key(|w| w.secs)
+                    .copied();
+                pending.retain(|w| secs > w.secs);
+                if let Some(warning) = due {
+                    let _ = tx.send(TimerEvent::Warning(warning));
+                }
             }
 
             if tx.send(TimerEvent::Tick(secs)).is_err() {
@@ -56,10 +88,16 @@ pub fn format_duration(total_secs: i64) -> String {
     format!("{}h {:02}m {:02}s", hours, minutes, seconds)
 }
 
-pub fn time_until_start(
-    start: &DateTime<FixedOffset>,
-) -> i64 {
-    let now = Utc::now();
+/// Seconds left in a section entered at `started_at` (RFC 3339) with a
+/// limit of `limit` seconds; negative once past it.
+pub fn section_remaining(started_at: &str, limit: i64, clock: &Clock) -> Option<i64> {
+    let started = DateTime::parse_from_rfc3339(started_at).ok()?;
+    let deadline = started + chrono::TimeDelta::seconds(limit);
+    Some(deadline.signed_duration_since(clock.now()).num_seconds())
+}
+
+pub fn time_until_start(start: &DateTime<FixedOffset>, clock: &Clock) -> i64 {
+    let now = clock.now();
     let remaining = start.signed_duration_since(now);
     remaining.num_seconds()
 }
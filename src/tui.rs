@@ -3,7 +3,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ratatui::crossterm::event::{
     self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
@@ -18,13 +18,20 @@ use ratatui::prelude::CrosstermBackend;
 use ratatui::Terminal;
 
 use crate::editor;
-use crate::model::QuestionKind;
+use crate::encrypt;
+use crate::manifest::{self, Manifest};
+use crate::model::{OnExpire, QuestionKind, WarningStyle};
 use crate::persist;
+use crate::sign;
 use crate::state::*;
 use crate::submit;
 use crate::timer::TimerEvent;
+use crate::writer::{self, Writer, WriterEvent};
 use crate::git;
 
+/// How long a `flash` warning highlights the titlebar timer.
+const FLASH_DURATION: Duration = Duration::from_secs(5);
+
 #[derive(Debug)]
 pub enum PushEvent {
     Success,
@@ -57,28 +64,17 @@ pub fn run_tui(
         let qnum = state.quiz.questions[0].number;
         state.visited.insert(qnum, true);
         state.load_text_input_for_current();
-        // Set initial input mode
-        if let Some(q) = state.current_question() {
-            match &q.kind {
-                QuestionKind::SingleChoice(_) | QuestionKind::MultiChoice(_) => {
-                    state.input_mode = InputMode::ChoiceSelect;
-                }
-                QuestionKind::Short | QuestionKind::Long => {
-                    state.input_mode = InputMode::TextInput;
-                }
-                _ => {
-                    state.input_mode = InputMode::Navigation;
-                }
-            }
-        }
+        state.update_input_mode();
     }
 
     let push_cancel = Arc::new(AtomicBool::new(false));
     let (push_tx, push_rx) = mpsc::channel::<PushEvent>();
+    let writer = writer::spawn_writer(&state);
 
     let result = main_loop(
         &mut terminal,
         &mut state,
+        &writer,
         &timer_rx,
         &push_rx,
         &push_tx,
@@ -89,18 +85,24 @@ pub fn run_tui(
     disable_raw_mode().ok();
     execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture).ok();
 
+    if let Some(ref e) = state.save_error {
+        eprintln!("Warning: answers may not be fully saved: {}", e);
+    }
+
     result
 }
 
 fn main_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     state: &mut AppState,
+    writer: &Writer,
     timer_rx: &mpsc::Receiver<TimerEvent>,
     push_rx: &mpsc::Receiver<PushEvent>,
     push_tx: &mpsc::Sender<PushEvent>,
     push_cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
     loop {
+        state.track_focus(state.screen == Screen::Working);
         terminal
             .draw(|f| crate::ui::draw(f, state))
             .map_err(|e| format!("Draw error: {}", e))?;
@@ -113,30 +115,41 @@ fn main_loop(
         if event::poll(Duration::from_millis(100))
             .map_err(|e| format!("Poll error: {}", e))?
         {
+            let question_before = state.current_question;
             match event::read().map_err(|e| format!("Read error: {}", e))? {
                 Event::Key(key) => {
-                    handle_key(key, state, terminal, push_tx, push_cancel)?;
-                    // Auto-save after key handling
-                    if state.screen == Screen::Working {
-                        let _ = persist::save_state(state);
-                    }
+                    handle_key(key, state, terminal, writer, push_tx, push_cancel)?;
                 }
                 Event::Mouse(mouse) => {
                     let size = terminal.size().unwrap_or_default();
                     let area = Rect::new(0, 0, size.width, size.height);
                     handle_mouse(mouse, state, area)?;
-                    // Auto-save after mouse handling
-                    if state.screen == Screen::Working {
-                        let _ = persist::save_state(state);
-                    }
                 }
                 _ => {}
             }
+
+            // Hand the change to the background writer; leaving a question
+            // writes it out right away
+            if state.screen == Screen::Working {
+                if state.current_question != question_before {
+                    writer.flush(state);
+                } else {
+                    writer.update(state);
+                }
+            }
+        }
+
+        // Handle writer events
+        while let Ok(ev) = writer.events.try_recv() {
+            match ev {
+                WriterEvent::Failed(e) => state.save_error = Some(e),
+                WriterEvent::Recovered => state.save_error = None,
+            }
         }
 
         // Handle timer events
         while let Ok(ev) = timer_rx.try_recv() {
-            handle_timer(ev, state, push_tx, push_cancel)?;
+            handle_timer(ev, state, writer, push_tx, push_cancel)?;
         }
 
         // Handle push events
@@ -152,12 +165,13 @@ fn handle_key(
     key: KeyEvent,
     state: &mut AppState,
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    writer: &Writer,
     push_tx: &mpsc::Sender<PushEvent>,
     push_cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
     // Handle dialog keys first
     if state.has_dialog() {
-        return handle_dialog_key(key, state, terminal, push_tx, push_cancel);
+        return handle_dialog_key(key, state, terminal, writer, push_tx, push_cancel);
     }
 
     match state.screen {
@@ -167,7 +181,11 @@ fn handle_key(
         Screen::Working => {
             handle_working_key(key, state, terminal, push_tx, push_cancel)
         }
-        Screen::Closed | Screen::AlreadySubmitted | Screen::Done | Screen::SaveLocal => {
+        Screen::Closed
+        | Screen::AlreadySubmitted
+        | Screen::Done
+        | Screen::SaveLocal
+        | Screen::Locked => {
             if key.code == KeyCode::Enter {
                 state.should_quit = true;
             }
@@ -209,7 +227,7 @@ fn handle_preamble_key(key: KeyEvent, state: &mut AppState) -> Result<(), String
             } else {
                 state.screen = Screen::Working;
                 if state.started_at.is_none() {
-                    state.started_at = Some(chrono::Utc::now().to_rfc3339());
+                    state.started_at = Some(state.clock.now().to_rfc3339());
                 }
             }
         }
@@ -256,27 +274,25 @@ fn handle_ack_key(key: KeyEvent, state: &mut AppState) -> Result<(), String> {
             _ => {}
         },
         AckFocus::Ok => match key.code {
-            KeyCode::Enter => {
-                if state.ack_name.len() >= 2 && state.ack_checkbox {
-                    let ack_text = state
-                        .quiz
-                        .frontmatter
-                        .acknowledgment
-                        .as_ref()
-                        .and_then(|a| a.text.as_ref())
-                        .cloned()
-                        .unwrap_or_default();
-
-                    state.ack_data = Some(crate::model::AckData {
-                        name: state.ack_name.clone(),
-                        agreed_at: chrono::Utc::now().to_rfc3339(),
-                        text_hash: persist::compute_str_hash(&ack_text),
-                    });
-                    state.screen = Screen::Working;
-                    state.input_mode = InputMode::Navigation;
-                    if state.started_at.is_none() {
-                        state.started_at = Some(chrono::Utc::now().to_rfc3339());
-                    }
+            KeyCode::Enter if state.ack_name.len() >= 2 && state.ack_checkbox => {
+                let ack_text = state
+                    .quiz
+                    .frontmatter
+                    .acknowledgment
+                    .as_ref()
+                    .and_then(|a| a.text.as_ref())
+                    .cloned()
+                    .unwrap_or_default();
+
+                state.ack_data = Some(crate::model::AckData {
+                    name: state.ack_name.clone(),
+                    agreed_at: state.clock.now().to_rfc3339(),
+                    text_hash: persist::compute_str_hash(&ack_text),
+                });
+                state.screen = Screen::Working;
+                state.input_mode = InputMode::Navigation;
+                if state.started_at.is_none() {
+                    state.started_at = Some(state.clock.now().to_rfc3339());
                 }
             }
             KeyCode::Tab => {
@@ -349,16 +365,15 @@ fn handle_working_key(
                 return Ok(());
             }
             KeyCode::Char('h') => {
-                let qnum = state.current_question_number();
-                if let Some(q) = state.current_question() {
-                    let revealed = state.hints_revealed.get(&qnum).copied().unwrap_or(0);
-                    if revealed < q.hints.len() {
-                        state.push_dialog(Dialog::ConfirmHint);
-                    }
+                if state.next_hint().is_some() {
+                    state.push_dialog(Dialog::ConfirmHint);
                 }
                 return Ok(());
             }
             KeyCode::Char('e') => {
+                if state.current_is_locked() {
+                    return Ok(());
+                }
                 if let Some(q) = state.current_question() {
                     if matches!(q.kind, QuestionKind::Long) {
                         let qnum = q.number;
@@ -375,6 +390,9 @@ fn handle_working_key(
 
                         match editor::open_editor(&current_text) {
                             Ok(new_text) => {
+                                if new_text != current_text {
+                                    state.mark_edited(qnum);
+                                }
                                 state.answers.insert(
                                     qnum,
                                     crate::model::Answer {
@@ -400,6 +418,9 @@ fn handle_working_key(
                 return Ok(());
             }
             KeyCode::Char('a') => {
+                if state.current_is_locked() {
+                    return Ok(());
+                }
                 if let Some(q) = state.current_question().cloned() {
                     if let QuestionKind::File(ref constraints) = q.kind {
                         // Check max files
@@ -433,11 +454,10 @@ fn handle_working_key(
                                     return Ok(());
                                 }
                                 // Copy to response dir
-                                match editor::copy_file_to_state(&path, &state.repo_dir, q.number) {
-                                    Ok(dest) => {
-                                        state.add_file(q.number, dest);
-                                    }
-                                    Err(_) => {}
+                                if let Ok(dest) =
+                                    editor::copy_file_to_state(&path, &state.state_dir, &q.key())
+                                {
+                                    state.add_file(q.number, dest);
                                 }
                             }
                             Ok(None) => {}
@@ -465,12 +485,8 @@ fn handle_working_key(
     if key.code == KeyCode::Char(' ') && !ctrl && state.main_focus != MainFocus::Answer {
         match state.main_focus {
             MainFocus::Hint => {
-                let qnum = state.current_question_number();
-                if let Some(q) = state.current_question() {
-                    let revealed = state.hints_revealed.get(&qnum).copied().unwrap_or(0);
-                    if revealed < q.hints.len() {
-                        state.push_dialog(Dialog::ConfirmHint);
-                    }
+                if state.next_hint().is_some() {
+                    state.push_dialog(Dialog::ConfirmHint);
                 }
             }
             MainFocus::DoneButton => {
@@ -498,38 +514,35 @@ fn handle_working_key(
 fn handle_text_input_key(key: KeyEvent, state: &mut AppState) -> Result<(), String> {
     let is_long = state
         .current_question()
-        .map_or(false, |q| matches!(q.kind, QuestionKind::Long));
+        .is_some_and(|q| matches!(q.kind, QuestionKind::Long));
 
+    let qnum = state.current_question_number();
     match key.code {
         KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
             state.text_input.insert(state.text_cursor, c);
             state.text_cursor += 1;
+            state.mark_edited(qnum);
         }
-        KeyCode::Backspace => {
-            if state.text_cursor > 0 {
-                state.text_cursor -= 1;
-                state.text_input.remove(state.text_cursor);
-            }
+        KeyCode::Backspace if state.text_cursor > 0 => {
+            state.text_cursor -= 1;
+            state.text_input.remove(state.text_cursor);
+            state.mark_edited(qnum);
         }
-        KeyCode::Delete => {
-            if state.text_cursor < state.text_input.len() {
-                state.text_input.remove(state.text_cursor);
-            }
+        KeyCode::Delete if state.text_cursor < state.text_input.len() => {
+            state.text_input.remove(state.text_cursor);
+            state.mark_edited(qnum);
         }
-        KeyCode::Left => {
-            if state.text_cursor > 0 {
-                state.text_cursor -= 1;
-            }
+        KeyCode::Left if state.text_cursor > 0 => {
+            state.text_cursor -= 1;
         }
-        KeyCode::Right => {
-            if state.text_cursor < state.text_input.len() {
-                state.text_cursor += 1;
-            }
+        KeyCode::Right if state.text_cursor < state.text_input.len() => {
+            state.text_cursor += 1;
         }
         KeyCode::Enter => {
             if is_long {
                 state.text_input.insert(state.text_cursor, '\n');
                 state.text_cursor += 1;
+                state.mark_edited(qnum);
             } else {
                 state.save_current_text_input();
                 navigate_next(state);
@@ -603,10 +616,7 @@ fn move_cursor_up(state: &mut AppState) {
     let lines: Vec<&str> = state.text_input.split('\n').collect();
     let target_row = row - 1;
     let target_col = col.min(lines[target_row].len());
-    let mut offset = 0;
-    for i in 0..target_row {
-        offset += lines[i].len() + 1;
-    }
+    let mut offset: usize = lines[..target_row].iter().map(|l| l.len() + 1).sum();
     offset += target_col;
     state.text_cursor = offset;
 }
@@ -619,10 +629,7 @@ fn move_cursor_down(state: &mut AppState) {
     }
     let target_row = row + 1;
     let target_col = col.min(lines[target_row].len());
-    let mut offset = 0;
-    for i in 0..target_row {
-        offset += lines[i].len() + 1;
-    }
+    let mut offset: usize = lines[..target_row].iter().map(|l| l.len() + 1).sum();
     offset += target_col;
     state.text_cursor = offset;
 }
@@ -680,9 +687,10 @@ fn handle_choice_key(key: KeyEvent, state: &mut AppState) -> Result<(), String>
 
 fn handle_nav_key(key: KeyEvent, state: &mut AppState) -> Result<(), String> {
     // Enter or typing a character resumes editing for text questions
-    let is_text_question = state.current_question().map_or(false, |q| {
-        matches!(q.kind, QuestionKind::Short | QuestionKind::Long)
-    });
+    let is_text_question = !state.current_is_locked()
+        && state
+            .current_question()
+            .is_some_and(|q| matches!(q.kind, QuestionKind::Short | QuestionKind::Long));
     if is_text_question {
         match key.code {
             KeyCode::Enter => {
@@ -695,6 +703,7 @@ fn handle_nav_key(key: KeyEvent, state: &mut AppState) -> Result<(), String> {
                 state.input_mode = InputMode::TextInput;
                 state.text_input.insert(state.text_cursor, c);
                 state.text_cursor += 1;
+                state.mark_edited(state.current_question_number());
                 return Ok(());
             }
             _ => {}
@@ -728,10 +737,8 @@ fn handle_page_keys(key: KeyEvent, state: &mut AppState) {
         KeyCode::Home => {
             state.navigate_to(0);
         }
-        KeyCode::End => {
-            if total > 0 {
-                state.navigate_to(total - 1);
-            }
+        KeyCode::End if total > 0 => {
+            state.navigate_to(total - 1);
         }
         _ => {}
     }
@@ -754,6 +761,7 @@ fn handle_dialog_key(
     key: KeyEvent,
     state: &mut AppState,
     _terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    writer: &Writer,
     push_tx: &mpsc::Sender<PushEvent>,
     push_cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
@@ -762,7 +770,7 @@ fn handle_dialog_key(
         Some(Dialog::ConfirmSubmit) => match key.code {
             KeyCode::Enter => {
                 state.pop_dialog();
-                do_submit(state, push_tx, push_cancel)?;
+                do_submit(state, writer, push_tx, push_cancel)?;
             }
             KeyCode::Esc => {
                 state.pop_dialog();
@@ -773,7 +781,9 @@ fn handle_dialog_key(
             KeyCode::Enter => {
                 state.pop_dialog();
                 state.save_current_text_input();
-                let _ = persist::save_state(state);
+                if let Err(e) = writer.sync(state) {
+                    state.save_error = Some(e);
+                }
                 state.should_quit = true;
             }
             KeyCode::Esc => {
@@ -784,17 +794,10 @@ fn handle_dialog_key(
         Some(Dialog::ConfirmHint) => match key.code {
             KeyCode::Enter => {
                 state.pop_dialog();
-                let qnum = state.current_question_number();
-                let current = state.hints_revealed.get(&qnum).copied().unwrap_or(0);
-                state.hints_revealed.insert(qnum, current + 1);
+                state.reveal_next_hint();
                 // If all hints now revealed and focus is on Hint, advance to DoneButton
-                if state.main_focus == MainFocus::Hint {
-                    let all_revealed = state.current_question().map_or(true, |q| {
-                        current + 1 >= q.hints.len()
-                    });
-                    if all_revealed {
-                        state.main_focus = MainFocus::DoneButton;
-                    }
+                if state.main_focus == MainFocus::Hint && state.next_hint().is_none() {
+                    state.main_focus = MainFocus::DoneButton;
                 }
             }
             KeyCode::Esc => {
@@ -805,7 +808,23 @@ fn handle_dialog_key(
         Some(Dialog::DoneRequiresAnswer) => {
             state.pop_dialog();
         }
-        Some(Dialog::TwoMinuteWarning) => match key.code {
+        Some(Dialog::TimeWarning(_)) => match key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                state.pop_dialog();
+            }
+            _ => {}
+        },
+        Some(Dialog::ConfirmSection(idx)) => match key.code {
+            KeyCode::Enter => {
+                state.pop_dialog();
+                state.advance_to(idx);
+            }
+            KeyCode::Esc => {
+                state.pop_dialog();
+            }
+            _ => {}
+        },
+        Some(Dialog::SectionExpired(_)) => match key.code {
             KeyCode::Enter | KeyCode::Esc => {
                 state.pop_dialog();
             }
@@ -817,6 +836,12 @@ fn handle_dialog_key(
             }
             _ => {}
         },
+        Some(Dialog::QuizUpdated) => match key.code {
+            KeyCode::Enter | KeyCode::Esc => {
+                state.pop_dialog();
+            }
+            _ => {}
+        },
         None => {}
     }
     Ok(())
@@ -825,6 +850,7 @@ fn handle_dialog_key(
 fn handle_timer(
     event: TimerEvent,
     state: &mut AppState,
+    writer: &Writer,
     push_tx: &mpsc::Sender<PushEvent>,
     push_cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
@@ -833,23 +859,79 @@ fn handle_timer(
             state.remaining_seconds = Some(secs);
 
             // Check if we transitioned from waiting
-            if state.screen == Screen::Waiting && secs <= 0 {
+            if state.screen == Screen::Waiting && state.clock.now() >= state.quiz.frontmatter.start
+            {
                 // Time to start
                 state.screen = Screen::Preamble;
             }
+
+            if state.screen == Screen::Working {
+                if let Some(section) = state.check_section_time() {
+                    state.push_dialog(Dialog::SectionExpired(section));
+                    writer.flush(state);
+                }
+            }
         }
-        TimerEvent::TwoMinuteWarning => {
-            if state.screen == Screen::Working && !state.has_dialog() {
-                state.push_dialog(Dialog::TwoMinuteWarning);
+        TimerEvent::Warning(warning) => {
+            if state.screen == Screen::Working {
+                // Never stack a warning over another dialog; flash instead
+                if warning.style == WarningStyle::Dialog && !state.has_dialog() {
+                    state.push_dialog(Dialog::TimeWarning(warning.secs));
+                } else {
+                    state.flash_until = Some(Instant::now() + FLASH_DURATION);
+                }
             }
         }
+        TimerEvent::ClockJump(jump) => {
+            state.clock_events.push(jump);
+            writer.flush(state);
+        }
         TimerEvent::TimeExpired => {
-            state.remaining_seconds = Some(0);
-            if state.screen == Screen::Working {
+            if state.quiz.frontmatter.grace == 0 {
+                expire(state, writer, push_tx, push_cancel)?;
+            } else if state.started_at.is_none() && is_before_work(&state.screen) {
+                // Nobody starts during the grace period
+                state.screen = Screen::Closed;
+            }
+        }
+        TimerEvent::GraceExpired => {
+            expire(state, writer, push_tx, push_cancel)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_before_work(screen: &Screen) -> bool {
+    matches!(
+        screen,
+        Screen::Waiting | Screen::Preamble | Screen::Acknowledgment
+    )
+}
+
+/// Time is up, including any grace period: apply `on_expire`.
+fn expire(
+    state: &mut AppState,
+    writer: &Writer,
+    push_tx: &mpsc::Sender<PushEvent>,
+    push_cancel: &Arc<AtomicBool>,
+) -> Result<(), String> {
+    let on_expire = state.quiz.frontmatter.on_expire;
+    match state.screen {
+        Screen::Working => match on_expire {
+            OnExpire::Submit => {
                 state.save_current_text_input();
-                do_submit(state, push_tx, push_cancel)?;
+                do_submit(state, writer, push_tx, push_cancel)?;
             }
+            OnExpire::Lock => {
+                state.save_current_text_input();
+                do_lock(state, writer)?;
+            }
+        },
+        // The window closed while waiting or reading
+        Screen::Waiting | Screen::Preamble | Screen::Acknowledgment => {
+            state.screen = Screen::Closed;
         }
+        _ => {}
     }
     Ok(())
 }
@@ -861,6 +943,8 @@ fn handle_push(
     match event {
         PushEvent::Success => {
             state.screen = Screen::Done;
+            // The submission is in the remote now; the draft is no longer needed
+            let _ = persist::clear_draft(&state.state_dir);
         }
         PushEvent::Retrying {
             attempt,
@@ -889,24 +973,32 @@ fn handle_push(
 
 fn do_submit(
     state: &mut AppState,
+    writer: &Writer,
     push_tx: &mpsc::Sender<PushEvent>,
     push_cancel: &Arc<AtomicBool>,
 ) -> Result<(), String> {
-    state.submitted_at = Some(chrono::Utc::now().to_rfc3339());
     state.screen = Screen::Pushing;
-
-    // Save state (writes response/answers.yaml)
-    let _ = persist::save_state(state);
-
-    // Copy file attachments
-    let repo_dir = state.repo_dir.clone();
-    submit::build_response(state, &repo_dir)?;
-
-    // Git add + commit
-    if git::is_git_repo(&repo_dir) {
-        let commit_msg = submit::build_commit_message(state);
+    let manifest = write_response(state, writer)?;
+    let repo_dir = state.submit_dir();
+
+    // Git add + commit; the tree hash ties the commit to the manifest
+    if state.is_rehearsal() {
+        let _ = push_tx.send(PushEvent::Success);
+    } else if git::is_git_repo(&repo_dir) {
+        let mut commit_msg = format!(
+            "{}\nManifest: {}",
+            submit::build_commit_message(state),
+            manifest.tree_hash
+        );
+        if state.quiz.frontmatter.recipient.is_some() {
+            let archive = repo_dir.join("response").join(encrypt::ARCHIVE_FILE);
+            commit_msg.push_str(&format!(
+                "\nEncrypted: {}",
+                persist::compute_file_hash(&archive)?
+            ));
+        }
         git::git_add(&repo_dir, &["response/"])?;
-        git::git_commit(&repo_dir, &commit_msg)?;
+        git::git_commit(&repo_dir, &commit_msg, state.signing_key.as_deref())?;
 
         // Push in background thread
         let tx = push_tx.clone();
@@ -925,6 +1017,32 @@ fn do_submit(
     Ok(())
 }
 
+/// `on_expire: lock`: write and sign response/ like a submit, but leave
+/// committing and pushing to the student.
+fn do_lock(state: &mut AppState, writer: &Writer) -> Result<(), String> {
+    write_response(state, writer)?;
+    state.screen = Screen::Locked;
+    Ok(())
+}
+
+/// Stamp the submission time, save the draft, then write response/ into the
+/// repo (or, in a rehearsal, next to its own draft) and sign it.
+fn write_response(state: &mut AppState, writer: &Writer) -> Result<Manifest, String> {
+    state.submitted_at = Some(state.clock.now().to_rfc3339());
+    if let Err(e) = writer.sync(state) {
+        state.save_error = Some(e);
+    }
+    let repo_dir = state.submit_dir();
+    let manifest = submit::build_response(state, &repo_dir)?;
+    if let Some(key) = &state.signing_key {
+        sign::sign_file(
+            key,
+            &repo_dir.join("response").join(manifest::MANIFEST_FILE),
+        )?;
+    }
+    Ok(manifest)
+}
+
 fn push_with_retry(
     repo_dir: std::path::PathBuf,
     tx: mpsc::Sender<PushEvent>,
@@ -1036,10 +1154,10 @@ fn handle_mouse(mouse: MouseEvent, state: &mut AppState, size: Rect) -> Result<(
                         state.toggle_status_filter(status_idx);
                     }
                 } else if relative_y < question_height {
-                    // Click on question list — use filtered list
-                    let filtered = state.filtered_questions();
-                    let current = state.current_question;
-                    let current_filtered_pos = filtered.iter().position(|&i| i == current);
+                    // Click on question list — use the rows as drawn
+                    let rows = state.sidebar_rows();
+                    let current = SidebarRow::Question(state.current_question);
+                    let current_filtered_pos = rows.iter().position(|&r| r == current);
 
                     let scroll_offset = if let Some(pos) = current_filtered_pos {
                         if pos >= state.sidebar_scroll + question_height {
@@ -1050,12 +1168,15 @@ fn handle_mouse(mouse: MouseEvent, state: &mut AppState, size: Rect) -> Result<(
                             state.sidebar_scroll
                         }
                     } else {
-                        state.sidebar_scroll.min(filtered.len().saturating_sub(question_height))
+                        state
+                            .sidebar_scroll
+                            .min(rows.len().saturating_sub(question_height))
                     };
 
-                    let filtered_click = scroll_offset + relative_y;
-                    if filtered_click < filtered.len() {
-                        let actual_idx = filtered[filtered_click];
+                    // Section headings are not clickable
+                    if let Some(&SidebarRow::Question(actual_idx)) =
+                        rows.get(scroll_offset + relative_y)
+                    {
                         state.navigate_to(actual_idx);
                         state.active_panel = ActivePanel::Main;
                     }
@@ -1117,11 +1238,9 @@ fn handle_mouse(mouse: MouseEvent, state: &mut AppState, size: Rect) -> Result<(
                 state.dragging_scrollbar = false;
             }
         }
-        MouseEventKind::Drag(MouseButton::Left) => {
-            if state.dragging_scrollbar {
-                let y = mouse.row;
-                scrollbar_navigate(state, y, sb_y_start, sb_y_end);
-            }
+        MouseEventKind::Drag(MouseButton::Left) if state.dragging_scrollbar => {
+            let y = mouse.row;
+            scrollbar_navigate(state, y, sb_y_start, sb_y_end);
         }
         MouseEventKind::Up(MouseButton::Left) => {
             state.dragging_scrollbar = false;
@@ -1142,10 +1261,9 @@ fn handle_mouse(mouse: MouseEvent, state: &mut AppState, size: Rect) -> Result<(
                 && x < layout.main.x + layout.main.width
                 && y >= layout.main.y
                 && y < layout.main.y + layout.main.height
+                && state.question_scroll > 0
             {
-                if state.question_scroll > 0 {
-                    state.question_scroll -= 1;
-                }
+                state.question_scroll -= 1;
             }
         }
         MouseEventKind::ScrollDown => {
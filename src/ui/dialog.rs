@@ -4,6 +4,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
 
+use crate::model::{format_points, points_label, OnExpire};
 use crate::state::{AppState, Dialog};
 
 pub fn draw_dialog(f: &mut Frame, area: Rect, state: &AppState) {
@@ -16,8 +17,11 @@ pub fn draw_dialog(f: &mut Frame, area: Rect, state: &AppState) {
         Dialog::ConfirmQuit => draw_confirm_quit(f, area, state),
         Dialog::ConfirmHint => draw_confirm_hint(f, area, state),
         Dialog::DoneRequiresAnswer => draw_done_requires_answer(f, area),
-        Dialog::TwoMinuteWarning => draw_two_minute_warning(f, area),
+        Dialog::TimeWarning(secs) => draw_time_warning(f, area, state, *secs),
+        Dialog::ConfirmSection(idx) => draw_confirm_section(f, area, state, *idx),
+        Dialog::SectionExpired(section) => draw_section_expired(f, area, state, *section),
         Dialog::Help => draw_help(f, area),
+        Dialog::QuizUpdated => draw_quiz_updated(f, area, state),
     }
 }
 
@@ -49,6 +53,13 @@ fn draw_confirm_submit(f: &mut Frame, area: Rect, state: &AppState) {
             Style::default().fg(Color::White),
         )));
     }
+    let points_left = state.unanswered_points();
+    if points_left > 0.0 {
+        msg_lines.push(Line::from(Span::styled(
+            format!("   {} unanswered.", points_label(points_left)),
+            Style::default().fg(Color::White),
+        )));
+    }
     if counts.flagged > 0 {
         msg_lines.push(Line::from(Span::styled(
             format!("   {} questions are flagged.", counts.flagged),
@@ -56,6 +67,28 @@ fn draw_confirm_submit(f: &mut Frame, area: Rect, state: &AppState) {
         )));
     }
 
+    // Where the time went, longest first, in whole minutes
+    let mut spent: Vec<(u32, u64)> = state
+        .quiz
+        .questions
+        .iter()
+        .map(|q| (q.number, state.time_on_question(q.number).as_secs() / 60))
+        .filter(|(_, mins)| *mins > 0)
+        .collect();
+    spent.sort_by_key(|&(_, mins)| std::cmp::Reverse(mins));
+    if !spent.is_empty() {
+        let longest: Vec<String> = spent
+            .iter()
+            .take(3)
+            .map(|(number, mins)| format!("Q{} {}m", number, mins))
+            .collect();
+        msg_lines.push(Line::from(""));
+        msg_lines.push(Line::from(Span::styled(
+            format!("   Most time: {}", longest.join(", ")),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
     msg_lines.push(Line::from(""));
     msg_lines.push(Line::from(vec![
         Span::styled(
@@ -67,7 +100,13 @@ fn draw_confirm_submit(f: &mut Frame, area: Rect, state: &AppState) {
     ]));
     msg_lines.push(Line::from(""));
 
-    let rect = centered_rect(42, msg_lines.len() as u16, area);
+    let width = msg_lines
+        .iter()
+        .map(|l| l.width() as u16 + 3)
+        .max()
+        .unwrap_or(42)
+        .clamp(42, area.width);
+    let rect = centered_rect(width, msg_lines.len() as u16, area);
     f.render_widget(Clear, rect);
     let block = Block::default()
         .borders(Borders::ALL)
@@ -105,8 +144,8 @@ fn draw_confirm_quit(f: &mut Frame, area: Rect, _state: &AppState) {
     f.render_widget(widget, rect);
 }
 
-fn draw_confirm_hint(f: &mut Frame, area: Rect, _state: &AppState) {
-    let lines = vec![
+fn draw_confirm_hint(f: &mut Frame, area: Rect, state: &AppState) {
+    let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled(
             "   Reveal hint?",
@@ -116,15 +155,23 @@ fn draw_confirm_hint(f: &mut Frame, area: Rect, _state: &AppState) {
         )),
         Line::from(""),
         Line::from("   This will be recorded."),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("   [Enter] Confirm", Style::default().fg(Color::Green)),
-            Span::raw("    "),
-            Span::styled("[Esc] Cancel", Style::default().fg(Color::DarkGray)),
-        ]),
-        Line::from(""),
     ];
 
+    if let Some(cost) = state.next_hint().and_then(|h| h.cost) {
+        lines.push(Line::from(Span::styled(
+            format!("   Cost: {} points.", format_points(cost)),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("   [Enter] Confirm", Style::default().fg(Color::Green)),
+        Span::raw("    "),
+        Span::styled("[Esc] Cancel", Style::default().fg(Color::DarkGray)),
+    ]));
+    lines.push(Line::from(""));
+
     let rect = centered_rect(38, lines.len() as u16, area);
     f.render_widget(Clear, rect);
     let block = Block::default()
@@ -162,18 +209,44 @@ fn draw_done_requires_answer(f: &mut Frame, area: Rect) {
     f.render_widget(widget, rect);
 }
 
-fn draw_two_minute_warning(f: &mut Frame, area: Rect) {
+fn draw_time_warning(f: &mut Frame, area: Rect, state: &AppState, secs: i64) {
+    let left = duration_words(secs);
+    let fm = &state.quiz.frontmatter;
+    let (first, second) = match (fm.on_expire, fm.grace > 0) {
+        (OnExpire::Submit, false) => (
+            "Your quiz will auto-submit when".to_string(),
+            "time expires. Save your work.",
+        ),
+        (OnExpire::Lock, false) => (
+            "Your answers will be locked when".to_string(),
+            "time expires. Save your work.",
+        ),
+        (OnExpire::Submit, true) => (
+            format!(
+                "Late work is accepted for {},",
+                duration_words(fm.grace).to_lowercase()
+            ),
+            "then your quiz auto-submits.",
+        ),
+        (OnExpire::Lock, true) => (
+            format!(
+                "Late work is accepted for {},",
+                duration_words(fm.grace).to_lowercase()
+            ),
+            "then your answers are locked.",
+        ),
+    };
     let lines = vec![
         Line::from(""),
         Line::from(Span::styled(
-            "   ⚠  2 MINUTES REMAINING",
+            format!("   ⚠  {} REMAINING", left),
             Style::default()
                 .fg(Color::Red)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from("   Your quiz will auto-submit when"),
-        Line::from("   time expires. Save your work."),
+        Line::from(format!("   {}", first)),
+        Line::from(format!("   {}", second)),
         Line::from(""),
         Line::from(Span::styled(
             "          [Enter] Continue",
@@ -182,7 +255,104 @@ fn draw_two_minute_warning(f: &mut Frame, area: Rect) {
         Line::from(""),
     ];
 
-    let rect = centered_rect(42, lines.len() as u16, area);
+    let width = lines
+        .iter()
+        .map(|l| l.width() as u16 + 3)
+        .max()
+        .unwrap_or(42)
+        .clamp(42, area.width);
+    let rect = centered_rect(width, lines.len() as u16, area);
+    f.render_widget(Clear, rect);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+    let widget = Paragraph::new(lines).block(block);
+    f.render_widget(widget, rect);
+}
+
+fn draw_confirm_section(f: &mut Frame, area: Rect, state: &AppState, idx: usize) {
+    let next = state.quiz.questions[idx]
+        .section
+        .map(|s| state.quiz.sections[s].title.as_str())
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("   Move on to {}?", next),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from("   Your answers so far will be locked."),
+        Line::from("   You cannot come back to change them."),
+    ];
+    if let Some(limit) = state.quiz.questions[idx]
+        .section
+        .and_then(|s| state.quiz.sections[s].time_limit)
+    {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "   The next section has {}.",
+                crate::timer::format_wait_duration(limit)
+            ),
+            Style::default().fg(Color::White),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("   [Enter] Confirm", Style::default().fg(Color::Green)),
+        Span::raw("    "),
+        Span::styled("[Esc] Cancel", Style::default().fg(Color::DarkGray)),
+    ]));
+    lines.push(Line::from(""));
+
+    let width = lines
+        .iter()
+        .map(|l| l.width() as u16 + 3)
+        .max()
+        .unwrap_or(44)
+        .clamp(44, area.width);
+    let rect = centered_rect(width, lines.len() as u16, area);
+    f.render_widget(Clear, rect);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let widget = Paragraph::new(lines).block(block);
+    f.render_widget(widget, rect);
+}
+
+fn draw_section_expired(f: &mut Frame, area: Rect, state: &AppState, section: usize) {
+    let title = &state.quiz.sections[section].title;
+    let next = if state.active_section().is_some() {
+        "   Moving on to the next section."
+    } else {
+        "   All sections are done; submit when ready."
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("   Time is up for {}", title),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from("   Its answers are now locked."),
+        Line::from(next),
+        Line::from(""),
+        Line::from(Span::styled(
+            "   [Enter] Continue",
+            Style::default().fg(Color::Green),
+        )),
+        Line::from(""),
+    ];
+
+    let width = lines
+        .iter()
+        .map(|l| l.width() as u16 + 3)
+        .max()
+        .unwrap_or(44)
+        .clamp(44, area.width);
+    let rect = centered_rect(width, lines.len() as u16, area);
     f.render_widget(Clear, rect);
     let block = Block::default()
         .borders(Borders::ALL)
@@ -191,6 +361,61 @@ fn draw_two_minute_warning(f: &mut Frame, area: Rect) {
     f.render_widget(widget, rect);
 }
 
+/// `secs` in the largest whole unit, e.g. `10 MINUTES`.
+fn duration_words(secs: i64) -> String {
+    match secs {
+        s if s >= 3600 && s % 3600 == 0 => plural(s / 3600, "HOUR"),
+        s if s >= 60 && s % 60 == 0 => plural(s / 60, "MINUTE"),
+        s => plural(s, "SECOND"),
+    }
+}
+
+fn plural(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}S", n, unit)
+    }
+}
+
+fn draw_quiz_updated(f: &mut Frame, area: Rect, state: &AppState) {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "   Changes since your last session",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for note in &state.migration_notes {
+        lines.push(Line::from(format!("   • {}", note)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "   [Enter] Continue",
+        Style::default().fg(Color::Green),
+    )));
+    lines.push(Line::from(""));
+
+    let width = lines
+        .iter()
+        .map(|l| l.width() as u16 + 4)
+        .max()
+        .unwrap_or(44)
+        .clamp(44, area.width);
+    let rect = centered_rect(width, lines.len() as u16 + 2, area);
+    f.render_widget(Clear, rect);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .wrap(ratatui::widgets::Wrap { trim: false });
+    f.render_widget(widget, rect);
+}
+
 fn draw_help(f: &mut Frame, area: Rect) {
     let lines = vec![
         Line::from(""),
@@ -0,0 +1,292 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use crate::model::CodeBlock;
+
+/// Lexical rules for one fenced-code language.
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    case_insensitive: bool,
+}
+
+#[rustfmt::skip]
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while",
+];
+
+#[rustfmt::skip]
+const C_KEYWORDS: &[&str] = &[
+    "auto", "bool", "break", "case", "catch", "char", "class", "const", "continue", "default",
+    "delete", "do", "double", "else", "enum", "extern", "false", "float", "for", "goto", "if",
+    "include", "inline", "int", "long", "namespace", "new", "nullptr", "private", "protected",
+    "public", "register", "return", "short", "signed", "sizeof", "static", "struct", "switch",
+    "template", "this", "throw", "true", "try", "typedef", "union", "unsigned", "using",
+    "virtual", "void", "volatile", "while",
+];
+
+#[rustfmt::skip]
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract", "boolean", "break", "byte", "case", "catch", "char", "class", "continue",
+    "default", "do", "double", "else", "extends", "false", "final", "finally", "float", "for",
+    "if", "implements", "import", "instanceof", "int", "interface", "long", "new", "null",
+    "package", "private", "protected", "public", "return", "short", "static", "super", "switch",
+    "this", "throw", "throws", "true", "try", "void", "while",
+];
+
+#[rustfmt::skip]
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+    "else", "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is",
+    "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True", "try",
+    "while", "with", "yield",
+];
+
+#[rustfmt::skip]
+const JS_KEYWORDS: &[&str] = &[
+    "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+    "delete", "do", "else", "export", "extends", "false", "finally", "for", "function", "if",
+    "import", "in", "instanceof", "interface", "let", "new", "null", "of", "return", "super",
+    "switch", "this", "throw", "true", "try", "type", "typeof", "undefined", "var", "void",
+    "while", "yield",
+];
+
+#[rustfmt::skip]
+const GO_KEYWORDS: &[&str] = &[
+    "break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+    "false", "for", "func", "go", "goto", "if", "import", "interface", "map", "nil", "package",
+    "range", "return", "select", "struct", "switch", "true", "type", "var",
+];
+
+#[rustfmt::skip]
+const SHELL_KEYWORDS: &[&str] = &[
+    "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in",
+    "local", "return", "then", "until", "while",
+];
+
+#[rustfmt::skip]
+const SQL_KEYWORDS: &[&str] = &[
+    "and", "as", "asc", "by", "create", "delete", "desc", "distinct", "drop", "from", "group",
+    "having", "in", "insert", "into", "is", "join", "left", "limit", "not", "null", "on", "or",
+    "order", "right", "select", "set", "table", "union", "update", "values", "where",
+];
+
+fn syntax_for(lang: &str) -> Option<Syntax> {
+    let c_like = |keywords| Syntax {
+        keywords,
+        line_comment: Some("//"),
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\''],
+        case_insensitive: false,
+    };
+    let hash_comment = |keywords, quotes| Syntax {
+        keywords,
+        line_comment: Some("#"),
+        block_comment: None,
+        quotes,
+        case_insensitive: false,
+    };
+
+    match lang {
+        "rust" | "rs" => Some(Syntax {
+            // No '\'' so lifetimes ('a) are not read as strings
+            quotes: &['"'],
+            ..c_like(RUST_KEYWORDS)
+        }),
+        "c" | "h" | "cpp" | "c++" | "cc" | "hpp" => Some(c_like(C_KEYWORDS)),
+        "java" | "kotlin" | "scala" => Some(c_like(JAVA_KEYWORDS)),
+        "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" => Some(Syntax {
+            quotes: &['"', '\'', '`'],
+            ..c_like(JS_KEYWORDS)
+        }),
+        "go" | "golang" => Some(Syntax {
+            quotes: &['"', '\'', '`'],
+            ..c_like(GO_KEYWORDS)
+        }),
+        "python" | "py" => Some(hash_comment(PYTHON_KEYWORDS, &['"', '\''])),
+        "sh" | "bash" | "shell" | "zsh" | "console" => {
+            Some(hash_comment(SHELL_KEYWORDS, &['"', '\'']))
+        }
+        "sql" => Some(Syntax {
+            keywords: SQL_KEYWORDS,
+            line_comment: Some("--"),
+            block_comment: Some(("/*", "*/")),
+            quotes: &['\''],
+            case_insensitive: true,
+        }),
+        _ => None,
+    }
+}
+
+fn plain_style() -> Style {
+    Style::default().fg(Color::White)
+}
+
+fn keyword_style() -> Style {
+    Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn string_style() -> Style {
+    Style::default().fg(Color::Yellow)
+}
+
+fn comment_style() -> Style {
+    Style::default()
+        .fg(Color::DarkGray)
+        .add_modifier(Modifier::ITALIC)
+}
+
+fn number_style() -> Style {
+    Style::default().fg(Color::Cyan)
+}
+
+/// Style used for every line of a block whose language is unknown or missing.
+fn fallback_style() -> Style {
+    Style::default().fg(Color::Green)
+}
+
+/// Render a fenced code block as indented lines, highlighted when the language is known.
+pub fn code_block_to_lines(block: &CodeBlock) -> Vec<Line<'static>> {
+    let syntax = block.lang.as_deref().and_then(syntax_for);
+    let code_lines: Vec<&str> = block.text.lines().collect();
+    let gutter_width = code_lines.len().to_string().len();
+    let mut in_block_comment = false;
+
+    code_lines
+        .iter()
+        .enumerate()
+        .map(|(i, code_line)| {
+            let mut spans = vec![Span::raw("  ")];
+            if block.line_numbers {
+                spans.push(Span::styled(
+                    format!("{:>w$} │ ", i + 1, w = gutter_width),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            match &syntax {
+                Some(syntax) => {
+                    spans.extend(highlight_line(code_line, syntax, &mut in_block_comment));
+                }
+                None => spans.push(Span::styled(code_line.to_string(), fallback_style())),
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Split one line of code into styled spans. `in_block_comment` carries
+/// multi-line comment state from the previous line.
+fn highlight_line(line: &str, syntax: &Syntax, in_block_comment: &mut bool) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut plain = String::new();
+    let mut rest = line;
+
+    let flush = |plain: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            spans.push(Span::styled(std::mem::take(plain), plain_style()));
+        }
+    };
+
+    while !rest.is_empty() {
+        if *in_block_comment {
+            let (_, close) = syntax.block_comment.unwrap_or(("", ""));
+            let end = rest.find(close).map_or(rest.len(), |p| {
+                *in_block_comment = false;
+                p + close.len()
+            });
+            spans.push(Span::styled(rest[..end].to_string(), comment_style()));
+            rest = &rest[end..];
+            continue;
+        }
+
+        if let Some(marker) = syntax.line_comment {
+            if rest.starts_with(marker) {
+                flush(&mut plain, &mut spans);
+                spans.push(Span::styled(rest.to_string(), comment_style()));
+                break;
+            }
+        }
+
+        if let Some((open, _)) = syntax.block_comment {
+            if rest.starts_with(open) {
+                flush(&mut plain, &mut spans);
+                *in_block_comment = true;
+                spans.push(Span::styled(open.to_string(), comment_style()));
+                rest = &rest[open.len()..];
+                continue;
+            }
+        }
+
+        let c = rest.chars().next().unwrap_or(' ');
+
+        if syntax.quotes.contains(&c) {
+            flush(&mut plain, &mut spans);
+            let end = string_end(rest, c);
+            spans.push(Span::styled(rest[..end].to_string(), string_style()));
+            rest = &rest[end..];
+            continue;
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|ch: char| {
+                    !(ch.is_alphanumeric() || ch == '_' || (ch == '.' && c.is_ascii_digit()))
+                })
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            let style = if c.is_ascii_digit() {
+                Some(number_style())
+            } else if is_keyword(word, syntax) {
+                Some(keyword_style())
+            } else {
+                None
+            };
+            match style {
+                Some(style) => {
+                    flush(&mut plain, &mut spans);
+                    spans.push(Span::styled(word.to_string(), style));
+                }
+                None => plain.push_str(word),
+            }
+            rest = &rest[end..];
+            continue;
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    flush(&mut plain, &mut spans);
+    spans
+}
+
+/// Byte offset just past the closing quote, or the end of the line if unterminated.
+fn string_end(s: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, ch) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == quote {
+            return i + ch.len_utf8();
+        }
+    }
+    s.len()
+}
+
+fn is_keyword(word: &str, syntax: &Syntax) -> bool {
+    if syntax.case_insensitive {
+        let lower = word.to_lowercase();
+        syntax.keywords.contains(&lower.as_str())
+    } else {
+        syntax.keywords.contains(&word)
+    }
+}
@@ -10,7 +10,7 @@ use crate::state::{AppState, InputMode, MainFocus};
 pub fn draw_keybar(f: &mut Frame, area: Rect, state: &AppState) {
     let is_long = state
         .current_question()
-        .map_or(false, |q| matches!(q.kind, QuestionKind::Long));
+        .is_some_and(|q| matches!(q.kind, QuestionKind::Long));
 
     let bindings: Vec<(&str, &str)> = if state.main_focus != MainFocus::Answer
         && state.input_mode != InputMode::AckNameInput
@@ -2,9 +2,13 @@ use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 
+use crate::math;
+use crate::ui::highlight::code_block_to_lines;
+
 pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_STRIKETHROUGH);
+    opts.insert(Options::ENABLE_MATH);
 
     let parser = Parser::new_ext(text, opts);
     let mut lines: Vec<Line<'static>> = Vec::new();
@@ -42,10 +46,8 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
                 current_spans.clear();
                 current_spans.push(Span::raw("  • "));
             }
-            Event::End(TagEnd::Item) => {
-                if !current_spans.is_empty() {
-                    lines.push(Line::from(std::mem::take(&mut current_spans)));
-                }
+            Event::End(TagEnd::Item) if !current_spans.is_empty() => {
+                lines.push(Line::from(std::mem::take(&mut current_spans)));
             }
             Event::Start(Tag::CodeBlock(_)) => {
                 current_spans.clear();
@@ -74,13 +76,7 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
             }
             Event::Text(text) => {
                 let style = *style_stack.last().unwrap_or(&Style::default());
-                // For code blocks, split by newlines
-                let t = text.to_string();
-                if style_stack.len() == 1 {
-                    current_spans.push(Span::styled(t, style));
-                } else {
-                    current_spans.push(Span::styled(t, style));
-                }
+                current_spans.push(Span::styled(text.to_string(), style));
             }
             Event::Code(code) => {
                 current_spans.push(Span::styled(
@@ -88,15 +84,28 @@ pub fn markdown_to_lines(text: &str) -> Vec<Line<'static>> {
                     Style::default().fg(Color::Yellow),
                 ));
             }
-            Event::SoftBreak => {
-                if !current_spans.is_empty() {
-                    lines.push(Line::from(std::mem::take(&mut current_spans)));
-                }
+            Event::InlineMath(latex) => {
+                let style = style_stack
+                    .last()
+                    .unwrap_or(&Style::default())
+                    .fg(Color::Cyan);
+                current_spans.push(Span::styled(math::to_unicode(&latex), style));
             }
-            Event::HardBreak => {
+            Event::DisplayMath(latex) => {
+                // Display math gets its own indented line
                 if !current_spans.is_empty() {
                     lines.push(Line::from(std::mem::take(&mut current_spans)));
                 }
+                lines.push(Line::from(vec![
+                    Span::raw("    "),
+                    Span::styled(math::to_unicode(&latex), Style::default().fg(Color::Cyan)),
+                ]));
+            }
+            Event::SoftBreak if !current_spans.is_empty() => {
+                lines.push(Line::from(std::mem::take(&mut current_spans)));
+            }
+            Event::HardBreak if !current_spans.is_empty() => {
+                lines.push(Line::from(std::mem::take(&mut current_spans)));
             }
             Event::Rule => {
                 lines.push(Line::from(Span::styled(
@@ -124,13 +133,8 @@ pub fn body_elements_to_lines(elements: &[crate::model::BodyElement]) -> Vec<Lin
                 let parsed = markdown_to_lines(text);
                 lines.extend(parsed);
             }
-            crate::model::BodyElement::Code(code) => {
-                for code_line in code.lines() {
-                    lines.push(Line::from(Span::styled(
-                        format!("  {}", code_line),
-                        Style::default().fg(Color::Green),
-                    )));
-                }
+            crate::model::BodyElement::Code(block) => {
+                lines.extend(code_block_to_lines(block));
                 lines.push(Line::from(""));
             }
             crate::model::BodyElement::Bold(text) => {
@@ -1,5 +1,6 @@
 pub mod ack;
 pub mod dialog;
+pub mod highlight;
 pub mod keybar;
 pub mod layout;
 pub mod markdown;
@@ -44,10 +45,18 @@ pub fn draw(f: &mut Frame, state: &AppState) {
         Screen::SaveLocal => {
             result::draw_save_local(f, area, state);
         }
+        Screen::Locked => {
+            result::draw_locked(f, area, state);
+        }
         Screen::Done => {
             result::draw_done(f, area, state);
         }
     }
+
+    // Dialog overlay; the working screen draws its own over the layout
+    if state.screen != Screen::Working && state.has_dialog() {
+        dialog::draw_dialog(f, area, state);
+    }
 }
 
 fn draw_working(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
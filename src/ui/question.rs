@@ -4,7 +4,7 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
 
-use crate::model::QuestionKind;
+use crate::model::{format_points, points_label, QuestionKind};
 use crate::state::{AppState, MainFocus};
 use crate::ui::markdown::body_elements_to_lines;
 
@@ -296,12 +296,25 @@ pub fn draw_question(f: &mut Frame, area: Rect, state: &AppState) {
     let mut lines: Vec<Line> = Vec::new();
 
     // Question header
-    lines.push(Line::from(Span::styled(
+    let mut header = vec![Span::styled(
         format!("  ## {}. {}", question.number, question.title),
         Style::default()
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD),
-    )));
+    )];
+    if let Some(points) = question.points {
+        header.push(Span::styled(
+            format!("  [{}]", points_label(points)),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if state.is_locked(question.number) {
+        header.push(Span::styled(
+            "  [locked]",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    lines.push(Line::from(header));
     lines.push(Line::from(""));
 
     // Question body (with wrapping)
@@ -312,7 +325,7 @@ pub fn draw_question(f: &mut Frame, area: Rect, state: &AppState) {
         for wline in wrapped {
             let indented = Line::from(
                 std::iter::once(Span::raw("  "))
-                    .chain(wline.spans.into_iter())
+                    .chain(wline.spans)
                     .collect::<Vec<_>>(),
             );
             lines.push(indented);
@@ -497,7 +510,7 @@ pub fn draw_question(f: &mut Frame, area: Rect, state: &AppState) {
 
             // Pre-compute lines after editor (hints + buttons)
             let mut after_count: usize = 0;
-            if question.hints.len() > 0 {
+            if !question.hints.is_empty() {
                 after_count += 1; // blank
                 let rev = state.hints_revealed.get(&qnum).copied().unwrap_or(0);
                 after_count += rev.min(question.hints.len());
@@ -673,7 +686,7 @@ pub fn draw_question(f: &mut Frame, area: Rect, state: &AppState) {
                     Style::default().fg(Color::DarkGray),
                 )));
             } else {
-                for (_i, file) in files.iter().enumerate() {
+                for file in files.iter() {
                     let filename = std::path::Path::new(file)
                         .file_name()
                         .unwrap_or_default()
@@ -720,9 +733,13 @@ pub fn draw_question(f: &mut Frame, area: Rect, state: &AppState) {
         lines.push(Line::from(""));
 
         // Show revealed hints
-        for i in 0..revealed.min(total_hints) {
+        for (i, hint) in question.hints.iter().take(revealed).enumerate() {
+            let cost = hint
+                .cost
+                .map(|c| format!(" (-{})", format_points(c)))
+                .unwrap_or_default();
             lines.push(Line::from(Span::styled(
-                format!("  💡 Hint {}: {}", i + 1, question.hints[i]),
+                format!("  💡 Hint {}{}: {}", i + 1, cost, hint.text),
                 Style::default().fg(Color::Yellow),
             )));
         }
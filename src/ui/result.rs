@@ -100,7 +100,7 @@ pub fn draw_push_retrying(f: &mut Frame, area: Rect, state: &AppState) {
 pub fn draw_save_local(f: &mut Frame, area: Rect, state: &AppState) {
     let repo_display = state.repo_dir.display().to_string();
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(""),
         Line::from(Span::styled(
             "✗  Submission Failed — Saved Locally",
@@ -114,31 +114,56 @@ pub fn draw_save_local(f: &mut Frame, area: Rect, state: &AppState) {
         Line::from(""),
         Line::from("To submit manually, run:"),
         Line::from(""),
+    ];
+    lines.extend(manual_submit_lines(&repo_display));
+    lines.extend([
+        Line::from(""),
+        Line::from("Contact your instructor if you need assistance."),
+        Line::from(""),
         Line::from(Span::styled(
-            format!("  cd {}", repo_display),
-            Style::default().fg(Color::Cyan),
-        )),
-        Line::from(Span::styled(
-            "  git add response/",
-            Style::default().fg(Color::Cyan),
-        )),
-        Line::from(Span::styled(
-            "  git commit -m \"termquiz: manual submit\"",
-            Style::default().fg(Color::Cyan),
+            "[Enter] Exit",
+            Style::default().fg(Color::DarkGray),
         )),
+        Line::from(""),
+    ]);
+
+    let block = Block::default().borders(Borders::ALL);
+    let widget = Paragraph::new(lines)
+        .block(block)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: false });
+    f.render_widget(widget, area);
+}
+
+/// `on_expire: lock`: time is up and the answers are written but not sent.
+pub fn draw_locked(f: &mut Frame, area: Rect, state: &AppState) {
+    let submit_dir = state.submit_dir().display().to_string();
+
+    let mut lines = vec![
+        Line::from(""),
         Line::from(Span::styled(
-            "  git push",
-            Style::default().fg(Color::Cyan),
+            "⏱  Time's Up — Answers Locked",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from("Contact your instructor if you need assistance."),
+        Line::from("Your answers have been saved to:"),
+        Line::from(format!("{}/response/", submit_dir)),
+        Line::from(""),
+        Line::from("They have not been submitted. Follow your instructor's"),
+        Line::from("directions, or to submit them now, run:"),
+        Line::from(""),
+    ];
+    lines.extend(manual_submit_lines(&submit_dir));
+    lines.extend([
         Line::from(""),
         Line::from(Span::styled(
             "[Enter] Exit",
             Style::default().fg(Color::DarkGray),
         )),
         Line::from(""),
-    ];
+    ]);
 
     let block = Block::default().borders(Borders::ALL);
     let widget = Paragraph::new(lines)
@@ -148,6 +173,18 @@ pub fn draw_save_local(f: &mut Frame, area: Rect, state: &AppState) {
     f.render_widget(widget, area);
 }
 
+fn manual_submit_lines(repo_display: &str) -> Vec<Line<'static>> {
+    [
+        format!("  cd {}", repo_display),
+        "  git add response/".to_string(),
+        "  git commit -m \"termquiz: manual submit\"".to_string(),
+        "  git push".to_string(),
+    ]
+    .into_iter()
+    .map(|cmd| Line::from(Span::styled(cmd, Style::default().fg(Color::Cyan))))
+    .collect()
+}
+
 pub fn draw_done(f: &mut Frame, area: Rect, state: &AppState) {
     let lines = vec![
         Line::from(""),
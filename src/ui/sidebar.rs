@@ -4,7 +4,8 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
 
-use crate::state::{ActivePanel, AppState, QuestionStatus};
+use crate::model::format_points;
+use crate::state::{ActivePanel, AppState, QuestionStatus, SidebarRow};
 
 const STATUS_ROWS: usize = 6; // 1 separator + 5 status lines
 
@@ -16,11 +17,13 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
     let question_height = inner_height.saturating_sub(STATUS_ROWS);
     let current = state.current_question;
     let total_questions = state.quiz.questions.len();
-    let filtered = state.filtered_questions();
-    let filtered_len = filtered.len();
+    let filtered_len = state.filtered_questions().len();
+    let rows = state.sidebar_rows();
 
-    // Find position of current question in filtered list (for auto-scroll)
-    let current_filtered_pos = filtered.iter().position(|&i| i == current);
+    // Find position of current question in the rows (for auto-scroll)
+    let current_filtered_pos = rows
+        .iter()
+        .position(|&r| r == SidebarRow::Question(current));
 
     // Auto-scroll sidebar based on filtered position
     let scroll_offset = if let Some(pos) = current_filtered_pos {
@@ -32,16 +35,36 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
             state.sidebar_scroll
         }
     } else {
-        state.sidebar_scroll.min(filtered_len.saturating_sub(question_height))
+        state
+            .sidebar_scroll
+            .min(rows.len().saturating_sub(question_height))
     };
 
-    let title_max_len = area.width.saturating_sub(11) as usize; // cursor+space+icon+space+number+dot+space
+    // Right-aligned points column, when the quiz has any
+    let points_width = state
+        .quiz
+        .questions
+        .iter()
+        .filter_map(|q| q.points)
+        .map(|p| format_points(p).len() + 1)
+        .max()
+        .unwrap_or(0);
+    let title_max_len = (area.width.saturating_sub(11) as usize) // cursor+space+icon+space+number+dot+space
+        .saturating_sub(points_width);
 
-    for &qi in filtered.iter().skip(scroll_offset) {
+    for &row in rows.iter().skip(scroll_offset) {
         if lines.len() >= question_height {
             break;
         }
 
+        let qi = match row {
+            SidebarRow::Question(qi) => qi,
+            SidebarRow::Section(s) => {
+                lines.push(section_line(state, s, inner_width));
+                continue;
+            }
+        };
+
         let q = &state.quiz.questions[qi];
         let status = state.question_status(q.number);
         let (icon, color) = match status {
@@ -75,15 +98,24 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
         };
 
         // Truncate title to fit
-        let title: String = q.title.chars().take(title_max_len).collect();
-        let title_display = if q.title.len() > title_max_len {
-            format!("{}…", &title[..title.len().saturating_sub(1)])
+        // Count chars, not bytes: titles may contain Unicode math
+        let title_display = if q.title.chars().count() > title_max_len {
+            let kept: String = q
+                .title
+                .chars()
+                .take(title_max_len.saturating_sub(1))
+                .collect();
+            format!("{}…", kept)
         } else {
-            title
+            q.title.clone()
         };
 
-        // Format: cursor + icon + number + title
-        let line = Line::from(vec![
+        let points = q.points.map(format_points).unwrap_or_default();
+        let title_pad = (title_max_len + points_width)
+            .saturating_sub(title_display.chars().count() + points.len());
+
+        // Format: cursor + icon + number + title + points
+        let mut spans = vec![
             Span::styled(
                 if is_current { " ▸ " } else { "   " }.to_string(),
                 style,
@@ -95,8 +127,17 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
             }),
             Span::styled(format!("{:>2}. ", q.number), style),
             Span::styled(title_display, style),
-        ]);
-        lines.push(line);
+        ];
+        if points_width > 0 {
+            spans.push(Span::styled(" ".repeat(title_pad), style));
+            let dim = if is_current {
+                Color::Gray
+            } else {
+                Color::DarkGray
+            };
+            spans.push(Span::styled(points, style.fg(dim)));
+        }
+        lines.push(Line::from(spans));
     }
 
     // Pad remaining question area with blank lines
@@ -153,7 +194,10 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
         Style::default()
     };
 
-    let sidebar_title = format!(" {} of {} Questions ", filtered_len, total_questions);
+    let mut sidebar_title = format!(" {} of {} Questions ", filtered_len, total_questions);
+    if let Some(total) = state.quiz.total_points() {
+        sidebar_title.push_str(&format!("· {} pts ", format_points(total)));
+    }
 
     let block = Block::default()
         .borders(Borders::RIGHT)
@@ -164,8 +208,8 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
     let widget = Paragraph::new(lines).block(block);
     f.render_widget(widget, area);
 
-    // Scrollbar — tracks filtered list
-    if filtered_len > question_height {
+    // Scrollbar — tracks the rows as drawn
+    if rows.len() > question_height {
         let scrollbar_area = Rect {
             x: area.x,
             y: area.y + 1,
@@ -173,10 +217,32 @@ pub fn draw_sidebar(f: &mut Frame, area: Rect, state: &AppState) {
             height: question_height as u16,
         };
         let sb_position = current_filtered_pos.unwrap_or(0);
-        let mut scrollbar_state = ScrollbarState::new(filtered_len.saturating_sub(1))
+        let mut scrollbar_state = ScrollbarState::new(rows.len().saturating_sub(1))
             .position(sb_position)
             .viewport_content_length(3);
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
         f.render_stateful_widget(scrollbar, scrollbar_area, &mut scrollbar_state);
     }
 }
+
+/// A section heading: its title, marked once the student is past it.
+fn section_line(state: &AppState, section: usize, width: usize) -> Line<'static> {
+    let locked = state
+        .sections
+        .get(section)
+        .is_some_and(|s| s.locked_at.is_some());
+    let (mark, color) = if locked {
+        (" (locked)", Color::DarkGray)
+    } else {
+        ("", Color::Cyan)
+    };
+    let title: String = state.quiz.sections[section]
+        .title
+        .chars()
+        .take(width.saturating_sub(2 + mark.chars().count()))
+        .collect();
+    Line::from(Span::styled(
+        format!(" {}{}", title, mark),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ))
+}
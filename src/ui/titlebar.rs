@@ -4,33 +4,80 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
-use crate::state::AppState;
+use crate::state::{AppState, Screen};
 use crate::timer::format_duration;
 
 pub fn draw_titlebar(f: &mut Frame, area: Rect, state: &AppState) {
     let title = &state.quiz.title;
 
+    let flashing = state
+        .flash_until
+        .is_some_and(|until| std::time::Instant::now() < until);
     let timer_text = if let Some(secs) = state.remaining_seconds {
-        let formatted = format!(" {} remaining ", format_duration(secs));
-        if secs <= 120 && secs > 0 {
-            Span::styled(
-                formatted,
-                Style::default()
-                    .fg(Color::White)
-                    .bg(Color::Red)
-                    .add_modifier(Modifier::BOLD),
-            )
+        let urgent = Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD);
+        if secs < 0 {
+            // Past the end: count down the grace period
+            let left = secs + state.quiz.frontmatter.grace;
+            Span::styled(format!(" LATE  {} left ", format_duration(left)), urgent)
         } else {
-            Span::styled(
-                formatted,
-                Style::default()
-                    .fg(Color::Rgb(200, 200, 120)),
-            )
+            let formatted = format!(" {} remaining ", format_duration(secs));
+            if flashing {
+                Span::styled(
+                    formatted,
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else if secs <= 120 && secs > 0 {
+                Span::styled(formatted, urgent)
+            } else {
+                Span::styled(formatted, Style::default().fg(Color::Rgb(200, 200, 120)))
+            }
         }
     } else {
         Span::raw("")
     };
 
+    // Pacing against question weights, while there is time left
+    let pace_span = match state.pace_seconds() {
+        Some(pace) if state.screen == Screen::Working => {
+            let minutes = pace.abs() / 60;
+            if minutes == 0 {
+                Span::styled(" on pace ", Style::default().fg(Color::Gray))
+            } else if pace > 0 {
+                Span::styled(
+                    format!(" {}m ahead ", minutes),
+                    Style::default().fg(Color::Green),
+                )
+            } else {
+                Span::styled(
+                    format!(" {}m behind ", minutes),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }
+        }
+        _ => Span::raw(""),
+    };
+
+    // The active section's own countdown, when it has a limit
+    let section_span = match state.section_remaining() {
+        Some((_, secs)) if state.screen == Screen::Working => {
+            let style = if secs <= 120 {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            Span::styled(format!(" section {} ", format_duration(secs)), style)
+        }
+        _ => Span::raw(""),
+    };
+
     let title_text = format!("[ {} ]", title);
     let title_span = Span::styled(
         title_text.clone(),
@@ -41,11 +88,9 @@ pub fn draw_titlebar(f: &mut Frame, area: Rect, state: &AppState) {
 
     // Center the title: pad left so title sits in the middle of the full width
     let available = area.width as usize;
-    let timer_len = if state.remaining_seconds.is_some() {
-        format_duration(state.remaining_seconds.unwrap_or(0)).len() + 13
-    } else {
-        0
-    };
+    let timer_len = timer_text.content.chars().count()
+        + pace_span.content.chars().count()
+        + section_span.content.chars().count();
     let title_len = title_text.len();
     let center_pad = if available > title_len {
         (available - title_len) / 2
@@ -55,10 +100,32 @@ pub fn draw_titlebar(f: &mut Frame, area: Rect, state: &AppState) {
     // Right padding fills the gap between centered title and right-aligned timer
     let right_pad = available.saturating_sub(center_pad + title_len + timer_len);
 
+    // Persistent write failures take the left edge so they cannot be missed
+    let (save_span, save_len) = match &state.save_error {
+        Some(_) => {
+            let text = " NOT SAVED ";
+            (
+                Span::styled(
+                    text,
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::Red)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                text.len(),
+            )
+        }
+        None => (Span::raw(""), 0),
+    };
+    let center_pad = center_pad.saturating_sub(save_len);
+
     let line = Line::from(vec![
+        save_span,
         Span::raw(" ".repeat(center_pad)),
         title_span,
         Span::raw(" ".repeat(right_pad)),
+        pace_span,
+        section_span,
         timer_text,
     ]);
 
@@ -8,7 +8,7 @@ use crate::state::AppState;
 use crate::timer::{format_wait_duration, time_until_start};
 
 pub fn draw_waiting(f: &mut Frame, area: Rect, state: &AppState) {
-    let secs = time_until_start(&state.quiz.frontmatter.start);
+    let secs = time_until_start(&state.quiz.frontmatter.start, &state.clock);
     let duration_str = format_wait_duration(secs);
 
     let lines = vec![
@@ -0,0 +1,148 @@
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::journal::{self, Journal};
+use crate::persist;
+use crate::schema::AnswersDoc;
+use crate::state::AppState;
+use crate::submit;
+
+/// How long changes may sit in memory before they are journaled.
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Wait before retrying a write that failed.
+const RETRY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone)]
+pub enum WriterEvent {
+    /// A write failed and is being retried; the message is for the UI.
+    Failed(String),
+    /// A write succeeded after earlier failures.
+    Recovered,
+}
+
+enum Request {
+    Update(Box<AnswersDoc>),
+    Flush(Box<AnswersDoc>, Option<mpsc::Sender<Result<u64, String>>>),
+}
+
+/// Handle to the background thread that journals answer changes, so the UI
+/// never waits on the disk while the student types.
+pub struct Writer {
+    tx: mpsc::Sender<Request>,
+    pub events: mpsc::Receiver<WriterEvent>,
+}
+
+impl Writer {
+    /// Queue the current state; it is written once the debounce expires.
+    pub fn update(&self, state: &AppState) {
+        let doc = submit::build_answers_doc(state);
+        let _ = self.tx.send(Request::Update(Box::new(doc)));
+    }
+
+    /// Write the current state now without waiting, e.g. on navigation.
+    pub fn flush(&self, state: &AppState) {
+        let doc = submit::build_answers_doc(state);
+        let _ = self.tx.send(Request::Flush(Box::new(doc), None));
+    }
+
+    /// Write the current state and wait until it is on disk, then take a
+    /// snapshot. Used on quit and submit.
+    pub fn sync(&self, state: &mut AppState) -> Result<(), String> {
+        let doc = submit::build_answers_doc(state);
+        let (done_tx, done_rx) = mpsc::channel();
+        self.tx
+            .send(Request::Flush(Box::new(doc), Some(done_tx)))
+            .map_err(|_| "Background writer has stopped".to_string())?;
+        let seq = done_rx
+            .recv()
+            .map_err(|_| "Background writer has stopped".to_string())??;
+        state.journal.seq = seq;
+        persist::save_state(state)
+    }
+}
+
+pub fn spawn_writer(state: &AppState) -> Writer {
+    let (tx, rx) = mpsc::channel::<Request>();
+    let (event_tx, event_rx) = mpsc::channel();
+    let state_dir = state.state_dir.clone();
+    let mut journal = state.journal.clone();
+
+    thread::spawn(move || {
+        let mut pending: Option<Box<AnswersDoc>> = None;
+        let mut deadline: Option<Instant> = None;
+        let mut failing = false;
+
+        loop {
+            let request = match deadline {
+                Some(at) => rx.recv_timeout(at.saturating_duration_since(Instant::now())),
+                None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+
+            let (doc, reply) = match request {
+                Ok(Request::Update(doc)) => {
+                    // Keep the first deadline so steady typing still gets
+                    // written every DEBOUNCE
+                    pending = Some(doc);
+                    deadline.get_or_insert_with(|| Instant::now() + DEBOUNCE);
+                    continue;
+                }
+                Ok(Request::Flush(doc, reply)) => (doc, reply),
+                Err(mpsc::RecvTimeoutError::Timeout) => match pending.take() {
+                    Some(doc) => (doc, None),
+                    None => {
+                        deadline = None;
+                        continue;
+                    }
+                },
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    if let Some(doc) = pending.take() {
+                        let _ = write(&mut journal, &state_dir, &doc);
+                    }
+                    break;
+                }
+            };
+
+            // A flush supersedes anything still waiting on the debounce
+            pending = None;
+            deadline = None;
+            let result = write(&mut journal, &state_dir, &doc);
+            match &result {
+                Ok(_) if failing => {
+                    failing = false;
+                    let _ = event_tx.send(WriterEvent::Recovered);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    failing = true;
+                    let _ = event_tx.send(WriterEvent::Failed(e.clone()));
+                    pending = Some(doc);
+                    deadline = Some(Instant::now() + RETRY);
+                }
+            }
+            if let Some(reply) = reply {
+                let _ = reply.send(result);
+            }
+        }
+    });
+
+    Writer {
+        tx,
+        events: event_rx,
+    }
+}
+
+/// Journal `doc`, compacting into a snapshot once enough entries piled up.
+/// Returns the journal's last sequence number.
+fn write(journal: &mut Journal, state_dir: &Path, doc: &AnswersDoc) -> Result<u64, String> {
+    journal.record(state_dir, doc)?;
+    if journal.since_snapshot >= journal::SNAPSHOT_EVERY {
+        let mut doc = doc.clone();
+        doc.session.journal_seq = Some(journal.seq);
+        persist::save_doc(state_dir, &doc)?;
+        journal.since_snapshot = 0;
+    }
+    Ok(journal.seq)
+}
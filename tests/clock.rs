@@ -0,0 +1,390 @@
+mod common;
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use termquiz::clock::{self, Clock, ClockMonitor};
+use termquiz::model::{AckData, OnExpire, Warning, WarningStyle};
+use termquiz::persist;
+use termquiz::state::{AppState, Screen};
+use termquiz::timer::{self, TimerEvent};
+
+use common::{at, quiz_with_frontmatter, sample_quiz};
+
+#[test]
+fn test_parse_simulated_time() {
+    let quiz = sample_quiz();
+    let (start, end) = (quiz.frontmatter.start, quiz.frontmatter.end);
+    let parse = |spec: &str| clock::parse_simulated_time(spec, start, end);
+
+    assert_eq!(parse("start").unwrap(), start);
+    assert_eq!(parse("end-2m30s").unwrap(), end - TimeDelta::seconds(150));
+    assert_eq!(parse("start+1h").unwrap(), start + TimeDelta::hours(1));
+    assert_eq!(
+        parse("2026-01-05T10:00:00Z").unwrap(),
+        at("2026-01-05T10:00:00Z")
+    );
+    for bad in [
+        "tomorrow",
+        "end-",
+        "end-5",
+        "end*2m",
+        "start+1d",
+        "end+9223372036854775807s",
+    ] {
+        assert!(parse(bad).is_err(), "{} should not parse", bad);
+    }
+}
+
+#[test]
+fn test_simulated_clock_speed() {
+    let start = at("2026-01-05T10:00:00Z");
+    let stopped = Clock::simulated(start, 0.0);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(stopped.now(), start);
+    assert!(stopped.is_simulated());
+    assert!(!Clock::system().is_simulated());
+
+    // An hour per real second
+    let fast = Clock::simulated(start, 3600.0);
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(fast.now() >= start + TimeDelta::seconds(60));
+
+    // Running past the end of time stops there instead of overflowing
+    let runaway = Clock::simulated(start, 1e300);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(runaway.now(), DateTime::<Utc>::MAX_UTC);
+}
+
+#[test]
+fn test_initial_screen_follows_clock() {
+    let quiz = sample_quiz();
+    let (start, end) = (
+        quiz.frontmatter.start.with_timezone(&Utc),
+        quiz.frontmatter.end.with_timezone(&Utc),
+    );
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    let screen_at = |state: &mut AppState, now: DateTime<Utc>| {
+        state.clock = Clock::simulated(now, 0.0);
+        state.initial_screen()
+    };
+
+    assert_eq!(
+        screen_at(&mut state, start - TimeDelta::minutes(5)),
+        Screen::Waiting
+    );
+    assert_eq!(screen_at(&mut state, start), Screen::Preamble);
+    assert_eq!(
+        screen_at(&mut state, end + TimeDelta::seconds(1)),
+        Screen::Closed
+    );
+
+    // Resuming a started quiz skips the preamble once acknowledged
+    state.started_at = Some(start.to_rfc3339());
+    assert_eq!(
+        screen_at(&mut state, start + TimeDelta::hours(1)),
+        Screen::Preamble
+    );
+    state.ack_data = Some(AckData {
+        name: "Jane".to_string(),
+        agreed_at: start.to_rfc3339(),
+        text_hash: "sha256:abc".to_string(),
+    });
+    assert_eq!(
+        screen_at(&mut state, start + TimeDelta::hours(1)),
+        Screen::Working
+    );
+}
+
+#[test]
+fn test_timer_runs_on_accelerated_clock() {
+    let end = sample_quiz().frontmatter.end;
+    // Two real seconds cover the last two simulated minutes
+    let clock = Clock::simulated(end.with_timezone(&Utc) - TimeDelta::seconds(121), 120.0);
+    let warnings = vec![Warning {
+        secs: 120,
+        style: WarningStyle::Dialog,
+    }];
+    let rx = timer::spawn_timer(end, 0, clock, warnings);
+
+    let mut events = Vec::new();
+    while let Ok(ev) = rx.recv_timeout(Duration::from_secs(5)) {
+        let expired = matches!(ev, TimerEvent::TimeExpired);
+        events.push(ev);
+        if expired {
+            break;
+        }
+    }
+    let first_tick = events.iter().find_map(|e| match e {
+        TimerEvent::Tick(secs) => Some(*secs),
+        _ => None,
+    });
+    assert!(first_tick.unwrap() > 100);
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, TimerEvent::Warning(w) if w.secs == 120)));
+    assert!(matches!(events.last(), Some(TimerEvent::TimeExpired)));
+    // An accelerated clock is not a tampered one
+    assert!(!events.iter().any(|e| matches!(e, TimerEvent::ClockJump(_))));
+}
+
+#[test]
+fn test_warning_schedule_and_on_expire() {
+    let fm = sample_quiz().frontmatter;
+    assert_eq!(
+        fm.warnings,
+        vec![Warning {
+            secs: 120,
+            style: WarningStyle::Dialog,
+        }]
+    );
+    assert_eq!(fm.on_expire, OnExpire::Submit);
+
+    let fm =
+        quiz_with_frontmatter("warnings: [30m, {at: 10m, style: flash}, 1m30s]\non_expire: lock\n")
+            .unwrap()
+            .frontmatter;
+    let secs: Vec<_> = fm.warnings.iter().map(|w| (w.secs, w.style)).collect();
+    assert_eq!(
+        secs,
+        vec![
+            (1800, WarningStyle::Dialog),
+            (600, WarningStyle::Flash),
+            (90, WarningStyle::Dialog),
+        ]
+    );
+    assert_eq!(fm.on_expire, OnExpire::Lock);
+
+    // No warnings at all is allowed
+    let fm = quiz_with_frontmatter("warnings: []\n").unwrap().frontmatter;
+    assert!(fm.warnings.is_empty());
+
+    // Late work is bounded by `grace`, not an expiry policy of its own
+    for bad in [
+        "warnings: [10]\n",
+        "warnings: [0m]\n",
+        "on_expire: later\n",
+        "on_expire: grace\n",
+    ] {
+        assert!(quiz_with_frontmatter(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_timer_fires_most_urgent_passed_warning_once() {
+    let end = sample_quiz().frontmatter.end;
+    // Starts inside both the 10m and 5m warnings, and stays there
+    let clock = Clock::simulated(end.with_timezone(&Utc) - TimeDelta::seconds(200), 0.0);
+    let warnings = vec![
+        Warning {
+            secs: 600,
+            style: WarningStyle::Dialog,
+        },
+        Warning {
+            secs: 300,
+            style: WarningStyle::Flash,
+        },
+    ];
+    let rx = timer::spawn_timer(end, 0, clock, warnings);
+
+    let mut fired = Vec::new();
+    let mut ticks = 0;
+    while ticks < 2 {
+        match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            TimerEvent::Warning(w) => fired.push(w.secs),
+            TimerEvent::Tick(_) => ticks += 1,
+            _ => {}
+        }
+    }
+    assert_eq!(fired, vec![300]);
+}
+
+#[test]
+fn test_late_submission_records_lateness() {
+    let quiz = quiz_with_frontmatter("grace: 2h\n").unwrap();
+    let end = quiz.frontmatter.end;
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+
+    state.submitted_at = Some((end - TimeDelta::seconds(30)).to_rfc3339());
+    let doc = termquiz::submit::build_answers_doc(&state);
+    assert_eq!(doc.quiz.late_by, None);
+
+    state.submitted_at = Some((end + TimeDelta::seconds(3725)).to_rfc3339());
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    assert!(yaml.contains("late_by: 01:02:05"), "{}", yaml);
+}
+
+#[test]
+fn test_grace_period_lets_started_students_resume() {
+    let quiz = quiz_with_frontmatter("grace: 15m\n").unwrap();
+    let fm = quiz.frontmatter.clone();
+    assert_eq!(fm.grace, 900);
+    assert_eq!(fm.grace_end(), fm.end + TimeDelta::minutes(15));
+    let end = fm.end.with_timezone(&Utc);
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    let screen_at = |state: &mut AppState, now: DateTime<Utc>| {
+        state.clock = Clock::simulated(now, 0.0);
+        state.initial_screen()
+    };
+
+    // Nobody starts late
+    assert_eq!(
+        screen_at(&mut state, end + TimeDelta::minutes(5)),
+        Screen::Closed
+    );
+
+    state.started_at = Some((end - TimeDelta::hours(1)).to_rfc3339());
+    state.ack_data = Some(AckData {
+        name: "Jane".to_string(),
+        agreed_at: (end - TimeDelta::hours(1)).to_rfc3339(),
+        text_hash: "sha256:abc".to_string(),
+    });
+    assert_eq!(
+        screen_at(&mut state, end + TimeDelta::minutes(5)),
+        Screen::Working
+    );
+    assert_eq!(
+        screen_at(&mut state, end + TimeDelta::minutes(16)),
+        Screen::Closed
+    );
+
+    // Plain seconds work too
+    let fm = quiz_with_frontmatter("grace: 900\n").unwrap().frontmatter;
+    assert_eq!(fm.grace, 900);
+    let fm = quiz_with_frontmatter("grace: 0\n").unwrap().frontmatter;
+    assert_eq!(fm.grace_end(), fm.end);
+
+    for bad in [
+        "grace: soon\n",
+        "grace: -60\n",
+        "grace: 169h\n",
+        "grace: 9223372036854775807\n",
+        "grace: 999999999999999h\n",
+    ] {
+        assert!(quiz_with_frontmatter(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_timer_signals_grace_expiry() {
+    let end = sample_quiz().frontmatter.end;
+    // Two real seconds cover end and a 60 second grace period
+    let clock = Clock::simulated(end.with_timezone(&Utc) - TimeDelta::seconds(30), 60.0);
+    let rx = timer::spawn_timer(end, 60, clock, Vec::new());
+
+    let mut events = Vec::new();
+    while let Ok(ev) = rx.recv_timeout(Duration::from_secs(5)) {
+        let over = matches!(ev, TimerEvent::GraceExpired);
+        events.push(ev);
+        if over {
+            break;
+        }
+    }
+    let expired = events
+        .iter()
+        .position(|e| matches!(e, TimerEvent::TimeExpired))
+        .unwrap();
+    assert!(matches!(events.last(), Some(TimerEvent::GraceExpired)));
+    // Ticks between the two count past the end
+    assert!(events[expired..]
+        .iter()
+        .any(|e| matches!(e, TimerEvent::Tick(secs) if *secs < 0)));
+}
+
+#[test]
+fn test_monitor_flags_wall_clock_jumps() {
+    let wall = at("2026-01-05T10:00:00Z");
+    let mono = Instant::now();
+    let mut monitor = ClockMonitor::new(wall, mono);
+
+    // Ordinary ticks, including a late one, are not jumps
+    assert!(monitor
+        .check(wall + TimeDelta::seconds(1), mono + Duration::from_secs(1))
+        .is_none());
+    assert!(monitor
+        .check(
+            wall + TimeDelta::milliseconds(3300),
+            mono + Duration::from_secs(3)
+        )
+        .is_none());
+
+    // Clock set back an hour between two ticks one second apart
+    let jump = monitor
+        .check(
+            wall + TimeDelta::seconds(4) - TimeDelta::hours(1),
+            mono + Duration::from_secs(4),
+        )
+        .unwrap();
+    assert_eq!(jump.source, "session");
+    assert_eq!(jump.offset_seconds, -3600);
+
+    // The next tick measures from the new wall time, so it is not reported again
+    assert!(monitor
+        .check(
+            wall + TimeDelta::seconds(5) - TimeDelta::hours(1),
+            mono + Duration::from_secs(5),
+        )
+        .is_none());
+}
+
+#[test]
+fn test_clock_behind_last_save_or_remote() {
+    let now = at("2026-01-05T10:00:00Z");
+    // A save a moment "ahead" is within the threshold
+    let saved = DateTime::parse_from_rfc3339("2026-01-05T11:00:03+01:00").unwrap();
+    assert!(clock::check_restart(saved, now).is_none());
+    let saved = DateTime::parse_from_rfc3339("2026-01-05T11:30:00+01:00").unwrap();
+    let event = clock::check_restart(saved, now).unwrap();
+    assert_eq!(
+        (event.source.as_str(), event.offset_seconds),
+        ("restart", -1800)
+    );
+
+    // Commit times from another machine get a few minutes of slack
+    let commit = DateTime::parse_from_rfc3339("2026-01-05T10:02:00Z").unwrap();
+    assert!(clock::check_remote(commit, now).is_none());
+    let commit = DateTime::parse_from_rfc3339("2026-01-06T10:00:00Z").unwrap();
+    assert_eq!(
+        clock::check_remote(commit, now).unwrap().offset_seconds,
+        -86400
+    );
+}
+
+#[test]
+fn test_clock_events_persist_and_accumulate() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_clock_events");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    let quiz = sample_quiz();
+    let mut state = AppState::new(quiz.clone(), tmp_dir.clone());
+    state.state_dir = tmp_dir.join("state");
+    let saved = DateTime::parse_from_rfc3339("2026-01-05T12:00:00Z").unwrap();
+    let event = clock::check_restart(saved, at("2026-01-05T10:00:00Z")).unwrap();
+    state.clock_events.push(event.clone());
+
+    let doc = termquiz::submit::build_answers_doc(&state);
+    state.journal.record(&state.state_dir, &doc).unwrap();
+    persist::save_state(&state).unwrap();
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    assert!(yaml.contains("clock_events:\n- at:"), "{}", yaml);
+    assert!(yaml.contains("source: restart"));
+
+    let mut restored = AppState::new(quiz, tmp_dir.clone());
+    restored.state_dir = tmp_dir.join("state");
+    persist::load_state(&mut restored).unwrap();
+    assert_eq!(restored.clock_events, vec![event.clone()]);
+
+    // The journal has them too, for answers reconstructed from it
+    let rebuilt = termquiz::journal::reconstruct(&restored, None).unwrap();
+    assert_eq!(rebuilt.clock_events, vec![event.clone()]);
+
+    // Restoring an older document without them does not drop any
+    let mut older = termquiz::submit::build_answers_doc(&restored);
+    older.clock_events.clear();
+    persist::restore_from_doc(&mut restored, &older);
+    assert_eq!(restored.clock_events, vec![event]);
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,84 @@
+//! Fixture builders shared by the integration tests.
+//!
+//! Each test binary uses only some of these.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::{DateTime, Utc};
+use termquiz::clock::Clock;
+use termquiz::model::{Answer, Quiz};
+use termquiz::state::AppState;
+
+pub fn at(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+}
+
+pub fn sample_quiz() -> Quiz {
+    quiz_with_frontmatter("").unwrap()
+}
+
+/// The sample quiz with extra frontmatter lines.
+pub fn quiz_with_frontmatter(extra: &str) -> Result<Quiz, String> {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let content = content.replacen("---\n", &format!("---\n{}", extra), 1);
+    termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc")
+}
+
+/// State whose working directory is inside the test's temp dir rather than
+/// the user's real state directory.
+pub fn new_state(quiz: Quiz, tmp_dir: &Path) -> AppState {
+    let mut state = AppState::new(quiz, tmp_dir.to_path_buf());
+    state.state_dir = tmp_dir.join("state");
+    state
+}
+
+pub fn sample_state(tmp_dir: &Path) -> AppState {
+    new_state(sample_quiz(), tmp_dir)
+}
+
+/// A started quiz with the clock stopped at `now`.
+pub fn working_state(quiz: Quiz, now: &str) -> AppState {
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    state.clock = Clock::simulated(at(now), 0.0);
+    state.started_at = Some(now.to_string());
+    state
+}
+
+pub fn text_answer(kind: &str, text: &str) -> Answer {
+    Answer {
+        answer_type: kind.to_string(),
+        selected: None,
+        text: Some(text.to_string()),
+        files: None,
+    }
+}
+
+pub fn short_answer(text: &str) -> Answer {
+    text_answer("short", text)
+}
+
+pub fn git(args: &[&str], cwd: &Path) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("git not available")
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Generate an unencrypted ed25519 key and return its private key path.
+pub fn keygen(dir: &Path, name: &str) -> PathBuf {
+    let key = dir.join(name);
+    let status = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-C", name, "-f"])
+        .arg(&key)
+        .status()
+        .expect("ssh-keygen not available");
+    assert!(status.success());
+    key
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::fs;
+
+use termquiz::encrypt;
+use termquiz::manifest;
+use termquiz::model::Answer;
+use termquiz::state::AppState;
+
+use common::keygen;
+
+fn quiz_with_recipient(recipient: &str) -> Result<termquiz::model::Quiz, String> {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let content = content.replacen("---\n", &format!("---\nrecipient: \"{}\"\n", recipient), 1);
+    termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc")
+}
+
+#[test]
+fn test_encrypted_response_decrypts_for_recipient() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_encrypt");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    let repo = tmp_dir.join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    let instructor = keygen(&tmp_dir, "instructor");
+    let other = keygen(&tmp_dir, "other");
+    let public = fs::read_to_string(instructor.with_extension("pub")).unwrap();
+
+    let quiz = quiz_with_recipient(public.trim()).unwrap();
+    let mut state = AppState::new(quiz, repo.clone());
+    state.state_dir = tmp_dir.join("state");
+    state.answers.insert(
+        3,
+        Answer {
+            answer_type: "short".to_string(),
+            selected: None,
+            text: Some("-i".to_string()),
+            files: None,
+        },
+    );
+    state.submitted_at = Some("2025-01-02T11:23:45-05:00".to_string());
+
+    let plain = termquiz::submit::build_response(&state, &repo).unwrap();
+
+    // Only the archive and its manifest reach the repo; no plaintext is left behind
+    let response = repo.join("response");
+    let mut names: Vec<String> = fs::read_dir(&response)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec![manifest::MANIFEST_FILE, encrypt::ARCHIVE_FILE]);
+    assert!(!state.state_dir.join("response").exists());
+    assert!(manifest::verify(&response).unwrap().is_empty());
+    assert!(termquiz::git::has_response_in_worktree(&repo));
+
+    // The wrong key cannot open it
+    let archive = response.join(encrypt::ARCHIVE_FILE);
+    let out = tmp_dir.join("decrypted");
+    assert!(encrypt::decrypt_archive(&archive, &other, &out).is_err());
+
+    encrypt::decrypt_archive(&archive, &instructor, &out).unwrap();
+    assert!(manifest::verify(&out).unwrap().is_empty());
+    let answers = fs::read_to_string(out.join("answers.yaml")).unwrap();
+    assert!(answers.contains("answer: -i"));
+    let inner: manifest::Manifest =
+        serde_yaml::from_str(&fs::read_to_string(out.join(manifest::MANIFEST_FILE)).unwrap())
+            .unwrap();
+    assert_eq!(inner.tree_hash, plain.tree_hash);
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_invalid_recipient_rejected() {
+    let err = quiz_with_recipient("ssh-ed25519 not-a-key").unwrap_err();
+    assert!(err.starts_with("Invalid recipient"), "{}", err);
+    let err = quiz_with_recipient("age1bogus").unwrap_err();
+    assert!(err.starts_with("Invalid recipient"), "{}", err);
+}
@@ -0,0 +1,90 @@
+use std::fs;
+
+use termquiz::export::{render, ExportFormat};
+use termquiz::model::Answer;
+use termquiz::state::AppState;
+
+fn answered_state() -> AppState {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc").unwrap();
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    state.answers.insert(
+        2,
+        Answer {
+            answer_type: "multi".to_string(),
+            selected: Some(vec!["a".to_string(), "d".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    state.answers.insert(
+        4,
+        Answer {
+            answer_type: "long".to_string(),
+            selected: None,
+            text: Some(
+                "Shared XOR mutable, \"checked\" at compile time.\n\nNo locks needed.".to_string(),
+            ),
+            files: None,
+        },
+    );
+    state.done_marks.insert(4, true);
+    state.flags.insert(2, true);
+    state
+}
+
+#[test]
+fn test_export_json_matches_schema() {
+    let json = render(&answered_state(), ExportFormat::Json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(value["schema_version"], 2);
+    assert_eq!(value["quiz"]["source"], "sample_quiz.md");
+    let questions = value["questions"].as_array().unwrap();
+    assert_eq!(questions.len(), 5);
+    assert_eq!(questions[1]["type"], "multi");
+    assert_eq!(questions[1]["answer"], serde_json::json!(["a", "d"]));
+    assert_eq!(questions[1]["flagged"], true);
+    assert_eq!(questions[2]["id"], "grep-case");
+    assert!(questions[2]["answer"].is_null());
+}
+
+#[test]
+fn test_export_csv_quotes_fields() {
+    let csv = render(&answered_state(), ExportFormat::Csv).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("number,id,title,type,status,answer,done,flagged,hints_used")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("1,q1,Multiple Choice (Single),single,unread,,false,false,0")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("2,q2,Multiple Choice (Multi),multi,flagged,a; d,false,true,0")
+    );
+    // Commas, quotes and newlines stay inside one quoted field
+    assert!(csv.contains(
+        "4,q4,Long Answer,long,done,\"Shared XOR mutable, \"\"checked\"\" at compile time.\n\nNo locks needed.\",true,false,0\n"
+    ));
+}
+
+#[test]
+fn test_export_markdown_transcript() {
+    let md = render(&answered_state(), ExportFormat::Md).unwrap();
+
+    assert!(md.starts_with("# Midterm Exam: Systems Programming\n"));
+    assert!(md.contains("- Submitted: not yet\n"));
+    assert!(md.contains("## 2. Multiple Choice (Multi)\n\nSelect all valid Rust integer types:\n"));
+    assert!(md.contains("- [x] a. i32\n- [ ] b. u64\n- [ ] c. int\n- [x] d. usize\n"));
+    assert!(md.contains("_flagged for review_"));
+    assert!(md.contains(
+        "**Answer:**\n\n> Shared XOR mutable, \"checked\" at compile time.\n>\n> No locks needed.\n\n_done_"
+    ));
+    assert!(md.contains(
+        "## 3. Short Answer\n\nWhat flag makes `grep` case-insensitive?\n\n*No answer.*"
+    ));
+    assert!(md.ends_with('\n') && !md.ends_with("\n\n"));
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use std::fs;
+
+use termquiz::export::ExportFormat;
+use termquiz::model::{Answer, Quiz};
+use termquiz::persist;
+
+use common::{new_state, text_answer};
+
+fn parse(fixture: &str, hash: &str) -> Quiz {
+    let content = fs::read_to_string(fixture).expect("Cannot read fixture");
+    termquiz::parser::parse_quiz(&content, "quiz.md", hash).unwrap()
+}
+
+#[test]
+fn test_import_merges_backup() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_import");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let backup = tmp_dir.join("backup.yaml");
+
+    // The laptop that died: answered Q1, Q3 and attached a file to Q5
+    let mut laptop = new_state(
+        parse("fixtures/sample_quiz.md", "sha256:abc"),
+        &tmp_dir.join("laptop"),
+    );
+    laptop.answers.insert(
+        1,
+        Answer {
+            answer_type: "single".to_string(),
+            selected: Some(vec!["b".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    laptop
+        .answers
+        .insert(3, text_answer("short", "--ignore-case"));
+    laptop.answers.insert(
+        5,
+        Answer {
+            answer_type: "file".to_string(),
+            selected: None,
+            text: None,
+            files: Some(vec!["/tmp/list.rs".to_string()]),
+        },
+    );
+    laptop.flags.insert(1, true);
+    persist::export_answers(&laptop, backup.to_str().unwrap(), ExportFormat::Yaml).unwrap();
+
+    // The lab machine has a different answer to Q3 already
+    let mut lab = new_state(
+        parse("fixtures/sample_quiz.md", "sha256:abc"),
+        &tmp_dir.join("lab"),
+    );
+    lab.answers.insert(3, text_answer("short", "-i"));
+
+    let (doc, changes) = persist::read_import(&lab, &backup).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(changes[0].contains("Q3") && changes[0].contains("\"-i\" -> \"--ignore-case\""));
+    assert!(changes[1].contains("Q5") && changes[1].contains("attach them again"));
+
+    persist::import_doc(&mut lab, &doc).unwrap();
+    assert_eq!(lab.answers[&1].selected, Some(vec!["b".to_string()]));
+    assert_eq!(lab.answers[&3].text.as_deref(), Some("--ignore-case"));
+    assert!(!lab.answers.contains_key(&5));
+    assert_eq!(lab.flags.get(&1), Some(&true));
+    assert!(lab.migration_notes.is_empty());
+
+    // The merge is saved like any other change
+    let mut reloaded = new_state(
+        parse("fixtures/sample_quiz.md", "sha256:abc"),
+        &tmp_dir.join("lab"),
+    );
+    assert!(persist::load_state(&mut reloaded).unwrap());
+    assert_eq!(reloaded.answers[&3].text.as_deref(), Some("--ignore-case"));
+    assert!(!termquiz::journal::read_entries(&lab.state_dir)
+        .unwrap()
+        .is_empty());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_import_rejects_other_quiz() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_import_mismatch");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let backup = tmp_dir.join("backup.yaml");
+
+    let mut other = new_state(
+        parse("fixtures/mc_quiz.md", "sha256:mc"),
+        &tmp_dir.join("other"),
+    );
+    other.answers.insert(
+        1,
+        Answer {
+            answer_type: "single".to_string(),
+            selected: Some(vec!["a".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    persist::export_answers(&other, backup.to_str().unwrap(), ExportFormat::Yaml).unwrap();
+
+    let state = new_state(
+        parse("fixtures/sample_quiz.md", "sha256:abc"),
+        &tmp_dir.join("lab"),
+    );
+    let err = persist::read_import(&state, &backup).unwrap_err();
+    assert!(err.contains("Multiple Choice Practice Quiz"));
+
+    // Same quiz, edited so that the saved Q3 no longer matches any question
+    let content = fs::read_to_string("fixtures/sample_quiz.md")
+        .unwrap()
+        .replace(
+            "## 3. Short Answer {#grep-case}",
+            "## 3. Short Answer {#grep-flag}",
+        );
+    let edited = termquiz::parser::parse_quiz(&content, "quiz.md", "sha256:edited").unwrap();
+    let mut old = new_state(
+        parse("fixtures/sample_quiz.md", "sha256:abc"),
+        &tmp_dir.join("old"),
+    );
+    old.answers.insert(3, text_answer("short", "-i"));
+    persist::export_answers(&old, backup.to_str().unwrap(), ExportFormat::Yaml).unwrap();
+
+    let state = new_state(edited, &tmp_dir.join("lab"));
+    let err = persist::read_import(&state, &backup).unwrap_err();
+    assert!(err.contains("Q3"));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use std::fs;
+
+use termquiz::journal;
+use termquiz::persist;
+use termquiz::state::AppState;
+
+use common::{sample_state, short_answer};
+
+/// Journal whatever changed in `state` since the last call.
+fn record(state: &mut AppState) -> usize {
+    let doc = termquiz::submit::build_answers_doc(state);
+    state.journal.record(&state.state_dir, &doc).unwrap()
+}
+
+/// Journal pending changes, then write the answers.yaml snapshot.
+fn snapshot(state: &mut AppState) {
+    record(state);
+    persist::save_state(state).unwrap();
+}
+
+#[test]
+fn test_journal_records_only_changes() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_journal_record");
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    let mut state = sample_state(&tmp_dir);
+    journal::mark_synced(&mut state);
+    assert_eq!(record(&mut state), 0);
+
+    state.answers.insert(3, short_answer("-i"));
+    state.flags.insert(3, true);
+    assert_eq!(record(&mut state), 2);
+    // Nothing changed since, so nothing is appended
+    assert_eq!(record(&mut state), 0);
+
+    state.answers.insert(3, short_answer("-v"));
+    record(&mut state);
+
+    let entries = journal::read_entries(&state.state_dir).unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries.iter().map(|e| e.seq).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    let last = &entries[2];
+    assert_eq!(last.question.as_deref(), Some("grep-case"));
+    assert_eq!(last.field, "answer");
+    assert_eq!(last.old, serde_json::json!("-i"));
+    assert_eq!(last.new, serde_json::json!("-v"));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_load_replays_journal_after_snapshot() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_journal_tail");
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    let mut state = sample_state(&tmp_dir);
+    state.answers.insert(3, short_answer("-i"));
+    snapshot(&mut state);
+    // The snapshot covers everything so far, so the live journal is rotated
+    assert!(journal::read_tail(&state.state_dir).unwrap().is_empty());
+    assert_eq!(journal::read_entries(&state.state_dir).unwrap().len(), 1);
+
+    // Changes after the snapshot only reach the journal, and still carry
+    // the value the snapshot holds
+    state.answers.insert(3, short_answer("--ignore-case"));
+    state.done_marks.insert(3, true);
+    record(&mut state);
+    let tail = journal::read_tail(&state.state_dir).unwrap();
+    let answer = tail.iter().find(|e| e.field == "answer").unwrap();
+    assert_eq!(answer.old, serde_json::json!("-i"));
+
+    let mut restored = sample_state(&tmp_dir);
+    assert!(persist::load_state(&mut restored).unwrap());
+    assert_eq!(restored.answers[&3].text.as_deref(), Some("--ignore-case"));
+    assert_eq!(restored.done_marks.get(&3), Some(&true));
+    assert_eq!(restored.journal.seq, state.journal.seq);
+    assert!(restored.migration_notes.is_empty());
+
+    // The whole history is still there for replay
+    let latest = journal::reconstruct(&state, None).unwrap();
+    let q3 = latest.questions.iter().find(|q| q.number == 3).unwrap();
+    assert_eq!(
+        q3.answer.as_ref().and_then(|a| a.as_text()),
+        Some("--ignore-case")
+    );
+    assert!(journal::last_write_time(&state.state_dir).is_some());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_torn_snapshot_recovered_from_journal() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_journal_torn");
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    let mut state = sample_state(&tmp_dir);
+    state.started_at = Some("2025-01-02T10:01:23-05:00".to_string());
+    state.answers.insert(3, short_answer("-i"));
+    state.flags.insert(1, true);
+    snapshot(&mut state);
+
+    // Simulate a crash halfway through rewriting the snapshot, and a torn
+    // last journal line
+    let yaml_path = state.state_dir.join("answers.yaml");
+    let yaml = fs::read_to_string(&yaml_path).unwrap();
+    fs::write(&yaml_path, &yaml[..yaml.len() / 3]).unwrap();
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal::journal_path(&state.state_dir))
+        .unwrap();
+    std::io::Write::write_all(&mut log, b"{\"seq\":99,\"at\":").unwrap();
+
+    let mut restored = sample_state(&tmp_dir);
+    assert!(persist::load_state(&mut restored).unwrap());
+    assert_eq!(restored.answers[&3].text.as_deref(), Some("-i"));
+    assert_eq!(restored.flags.get(&1), Some(&true));
+    assert_eq!(
+        restored.started_at.as_deref(),
+        Some("2025-01-02T10:01:23-05:00")
+    );
+    // Recovery is reported in the startup notes rather than printed
+    assert_eq!(restored.migration_notes.len(), 1);
+    assert!(restored.migration_notes[0].contains("recovered from the journal"));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_replay_until_point_in_time() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_journal_replay");
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    let mut state = sample_state(&tmp_dir);
+    state.answers.insert(3, short_answer("-i"));
+    record(&mut state);
+    state.answers.insert(3, short_answer("-v"));
+    record(&mut state);
+
+    // Move the second change an hour later so the cut-off is unambiguous
+    let path = journal::journal_path(&state.state_dir);
+    let mut entries = journal::read_entries(&state.state_dir).unwrap();
+    let first = chrono::DateTime::parse_from_rfc3339(&entries[0].at).unwrap();
+    entries[1].at = (first + chrono::Duration::hours(1)).to_rfc3339();
+    let lines: Vec<String> = entries
+        .iter()
+        .map(|e| serde_json::to_string(e).unwrap())
+        .collect();
+    fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+    let at_first = journal::reconstruct(&state, Some(first)).unwrap();
+    let q3 = at_first.questions.iter().find(|q| q.number == 3).unwrap();
+    assert_eq!(q3.answer.as_ref().and_then(|a| a.as_text()), Some("-i"));
+
+    let latest = journal::reconstruct(&state, None).unwrap();
+    let q3 = latest.questions.iter().find(|q| q.number == 3).unwrap();
+    assert_eq!(q3.answer.as_ref().and_then(|a| a.as_text()), Some("-v"));
+
+    let before = first - chrono::Duration::minutes(1);
+    let empty = journal::reconstruct(&state, Some(before)).unwrap();
+    assert!(empty.questions.iter().all(|q| q.answer.is_none()));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,73 @@
+use std::fs;
+
+use termquiz::manifest;
+use termquiz::model::Answer;
+use termquiz::persist::{compute_file_hash, compute_str_hash};
+use termquiz::state::AppState;
+
+#[test]
+fn test_manifest_lists_and_verifies_response() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_manifest");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let upload = tmp_dir.join("list.rs");
+    fs::write(&upload, "struct Node;").unwrap();
+
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc").unwrap();
+    let mut state = AppState::new(quiz, tmp_dir.clone());
+    state.state_dir = tmp_dir.join("state");
+    let staged =
+        termquiz::editor::copy_file_to_state(upload.to_str().unwrap(), &state.state_dir, "q5")
+            .unwrap();
+    state.answers.insert(
+        5,
+        Answer {
+            answer_type: "file".to_string(),
+            selected: None,
+            text: None,
+            files: Some(vec![staged]),
+        },
+    );
+    state.submitted_at = Some("2025-01-02T11:23:45-05:00".to_string());
+
+    let built = termquiz::submit::build_response(&state, &tmp_dir).unwrap();
+    let response = tmp_dir.join("response");
+    assert!(response.join(manifest::MANIFEST_FILE).exists());
+
+    let paths: Vec<&str> = built.files.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, vec!["answers.yaml", "files/q5/list.rs"]);
+    let file = &built.files[1];
+    assert_eq!(file.size, 12);
+    assert_eq!(file.sha256, compute_str_hash("struct Node;"));
+    assert_eq!(file.question.as_deref(), Some("q5"));
+    assert_eq!(file.original_name.as_deref(), Some("list.rs"));
+    assert!(file.attached_at.is_some());
+    assert_eq!(
+        built.files[0].sha256,
+        compute_file_hash(&response.join("answers.yaml")).unwrap()
+    );
+    assert!(built.tree_hash.starts_with("sha256:"));
+
+    assert!(manifest::verify(&response).unwrap().is_empty());
+
+    // Any change after submit shows up
+    fs::write(
+        response.join("files/q5/list.rs"),
+        "struct Node { next: usize }",
+    )
+    .unwrap();
+    fs::write(response.join("files/q5/extra.rs"), "fn main() {}").unwrap();
+    fs::remove_file(response.join("answers.yaml")).unwrap();
+    let problems = manifest::verify(&response).unwrap();
+    assert_eq!(
+        problems,
+        vec![
+            "answers.yaml: missing",
+            "files/q5/list.rs: modified",
+            "files/q5/extra.rs: not in manifest",
+        ]
+    );
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
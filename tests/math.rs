@@ -0,0 +1,82 @@
+use termquiz::math::to_unicode;
+use termquiz::model::BodyElement;
+use termquiz::ui::markdown::body_elements_to_lines;
+
+#[test]
+fn test_common_notation() {
+    assert_eq!(to_unicode(r"O(n \log n)"), "O(n log n)");
+    assert_eq!(to_unicode(r"\sum_{i=1}^n i"), "∑ᵢ₌₁ⁿ i");
+    assert_eq!(to_unicode(r"\alpha + \beta \leq \pi"), "α + β ≤ π");
+    assert_eq!(to_unicode(r"x^2 + y_{0}"), "x² + y₀");
+    assert_eq!(to_unicode(r"2^{n-1}"), "2ⁿ⁻¹");
+    assert_eq!(to_unicode(r"\mathbb{R}^n"), "ℝⁿ");
+}
+
+#[test]
+fn test_fractions_and_roots() {
+    assert_eq!(to_unicode(r"\frac{1}{2}"), "½");
+    assert_eq!(to_unicode(r"\frac{a}{b}"), "a/b");
+    assert_eq!(to_unicode(r"\frac{n+1}{2}"), "(n+1)/2");
+    assert_eq!(to_unicode(r"\sqrt{x}"), "√x");
+    assert_eq!(to_unicode(r"\sqrt{n+1}"), "√(n+1)");
+    assert_eq!(to_unicode(r"\sqrt[3]{8}"), "∛8");
+}
+
+#[test]
+fn test_readable_fallback() {
+    // No superscript q, so the exponent is spelled out
+    assert_eq!(to_unicode(r"x^{q+1}"), "x^(q+1)");
+    // Unknown commands stay visible
+    assert_eq!(to_unicode(r"\foo{x}"), r"\foo{x}");
+    assert_eq!(to_unicode(r"\foo{\alpha}{2} + 1"), r"\foo{α}{2} + 1");
+    assert_eq!(to_unicode(r"\text{if } x > 0"), "if x > 0");
+    // Unbalanced input does not panic
+    assert_eq!(to_unicode(r"\frac{1}{"), "1/");
+}
+
+#[test]
+fn test_math_in_quiz() {
+    let content = r#"---
+title: "Algorithms"
+start: 2025-01-01T00:00:00-05:00
+end: 2030-12-31T23:59:59-05:00
+---
+
+# Algorithms
+
+Costs are given as $O(f(n))$.
+
+## 1. Cost of $\sum_{i=1}^n i$
+
+Which bound holds for $n \ge 1$?
+
+- [ ] $\Theta(n^2)$
+- [ ] $O(n \log n)$
+
+:::hint
+Gauss: $\frac{n(n+1)}{2}$
+:::
+"#;
+    let quiz = termquiz::parser::parse_quiz(content, "quiz.md", "sha256:test").unwrap();
+    assert_eq!(quiz.preamble, vec!["Costs are given as O(f(n))."]);
+
+    let q = &quiz.questions[0];
+    assert_eq!(q.title, "Cost of ∑ᵢ₌₁ⁿ i");
+    assert_eq!(q.hints[0].text, "Gauss: n(n+1)/2");
+    match &q.kind {
+        termquiz::model::QuestionKind::SingleChoice(choices) => {
+            assert_eq!(choices[0].text, "Θ(n²)");
+            assert_eq!(choices[1].text, "O(n log n)");
+        }
+        other => panic!("Expected SingleChoice, got {:?}", other),
+    }
+
+    // Body text keeps the source math; the markdown renderer converts it
+    match &q.body_lines[0] {
+        BodyElement::Text(text) => assert_eq!(text, r"Which bound holds for $n \ge 1$?"),
+        other => panic!("Expected Text, got {:?}", other),
+    }
+    let lines = body_elements_to_lines(&q.body_lines);
+    let rendered: String = lines[0].spans.iter().map(|s| s.content.as_ref()).collect();
+    assert_eq!(rendered, "Which bound holds for n ≥ 1?");
+}
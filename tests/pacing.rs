@@ -0,0 +1,124 @@
+mod common;
+
+use chrono::TimeDelta;
+use termquiz::model::Quiz;
+use termquiz::state::AppState;
+
+use common::short_answer;
+
+fn weighted_quiz(weights: [&str; 3]) -> Result<Quiz, String> {
+    weighted_quiz_with("", weights)
+}
+
+/// Three short questions with the given heading attributes.
+fn weighted_quiz_with(extra: &str, weights: [&str; 3]) -> Result<Quiz, String> {
+    let content = format!(
+        "---\nstart: 2026-01-05T10:00:00Z\nend: 2026-01-05T11:00:00Z\n{}---\n\n# Weighted\n\n\
+         ## 1. First {}\n\nOne?\n\n> short\n\n\
+         ## 2. Second {}\n\nTwo?\n\n> short\n\n\
+         ## 3. Third {}\n\nThree?\n\n> short\n",
+        extra, weights[0], weights[1], weights[2]
+    );
+    termquiz::parser::parse_quiz(&content, "weighted.md", "sha256:abc")
+}
+
+fn answer(state: &mut AppState, qnum: u32) {
+    state.answers.insert(qnum, short_answer("x"));
+}
+
+#[test]
+fn test_heading_weights() {
+    let quiz = weighted_quiz(["{#first points=2}", "{points=1.5 minutes=10}", ""]).unwrap();
+    let q = &quiz.questions;
+    assert_eq!(q[0].id.as_deref(), Some("first"));
+    assert_eq!((q[0].points, q[0].minutes), (Some(2.0), None));
+    assert_eq!((q[1].points, q[1].minutes), (Some(1.5), Some(10.0)));
+    assert_eq!(q[1].title, "Second");
+    assert_eq!((q[2].points, q[2].minutes), (None, None));
+
+    for bad in ["{points=lots}", "{minutes=-5}", "{points}"] {
+        let err = weighted_quiz([bad, "", ""]).unwrap_err();
+        assert!(err.contains("on question 1"), "{}", err);
+    }
+}
+
+#[test]
+fn test_pace_from_minutes() {
+    let quiz = weighted_quiz(["{minutes=10}", "{minutes=20}", "{minutes=5}"]).unwrap();
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    assert_eq!(state.pace_seconds(), None);
+
+    // 30 minutes left, 35 estimated
+    state.remaining_seconds = Some(30 * 60);
+    assert_eq!(state.pace_seconds(), Some(-5 * 60));
+    answer(&mut state, 2);
+    assert_eq!(state.pace_seconds(), Some(15 * 60));
+    // Flagged but answered still counts as answered
+    state.flags.insert(2, true);
+    assert_eq!(state.pace_seconds(), Some(15 * 60));
+
+    // No pacing once time is up
+    state.remaining_seconds = Some(-10);
+    assert_eq!(state.pace_seconds(), None);
+}
+
+#[test]
+fn test_pace_from_points() {
+    let quiz = weighted_quiz(["{points=1}", "{points=1}", "{points=8}"]).unwrap();
+    let start = quiz.frontmatter.start;
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    state.started_at = Some(start.to_rfc3339());
+    // Working on the last question; the current one's live text counts
+    state.current_question = 2;
+
+    // Half of the hour left with the one-pointers done: 80% of the points
+    // remain, worth 48 minutes
+    state.remaining_seconds = Some(30 * 60);
+    answer(&mut state, 1);
+    answer(&mut state, 2);
+    assert_eq!(state.pace_seconds(), Some(-18 * 60));
+
+    // Starting late shrinks the budget
+    state.started_at = Some((start + TimeDelta::minutes(30)).to_rfc3339());
+    assert_eq!(state.pace_seconds(), Some(6 * 60));
+
+    // Without weights there is no pace
+    let mut state = AppState::new(weighted_quiz(["", "", ""]).unwrap(), std::env::temp_dir());
+    state.remaining_seconds = Some(600);
+    assert_eq!(state.pace_seconds(), None);
+}
+
+#[test]
+fn test_points_total_and_submission() {
+    let quiz = weighted_quiz(["{points=2}", "{points=3}", ""]).unwrap();
+    assert_eq!(quiz.total_points(), Some(5.0));
+    let mut state = AppState::new(quiz, std::env::temp_dir());
+    state.current_question = 2;
+    answer(&mut state, 1);
+    assert_eq!(state.unanswered_points(), 3.0);
+    assert_eq!(termquiz::model::points_label(3.0), "3 points");
+    assert_eq!(termquiz::model::points_label(1.0), "1 point");
+
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(parsed["quiz"]["total_points"].as_f64(), Some(5.0));
+    assert_eq!(parsed["questions"][1]["points"].as_f64(), Some(3.0));
+    assert!(parsed["questions"][2].get("points").is_none());
+
+    let md = termquiz::export::render(&state, termquiz::export::ExportFormat::Md).unwrap();
+    assert!(md.contains("- Total: 5 points"), "{}", md);
+    assert!(md.contains("## 2. Second (3 points)"), "{}", md);
+}
+
+#[test]
+fn test_frontmatter_total_points() {
+    let with_total =
+        |total: &str, weights| weighted_quiz_with(&format!("total_points: {}\n", total), weights);
+
+    // A total alone is fine, and is what the quiz reports
+    let quiz = with_total("40", ["", "", ""]).unwrap();
+    assert_eq!(quiz.total_points(), Some(40.0));
+    assert!(with_total("10", ["{points=5}", "{points=5}", ""]).is_ok());
+    let err = with_total("12", ["{points=5}", "{points=5}", ""]).unwrap_err();
+    assert!(err.contains("add up to 10"), "{}", err);
+}
@@ -17,7 +17,7 @@ fn test_parse_sample_quiz() {
             assert_eq!(choices.len(), 4);
             assert_eq!(choices[0].text, "exec");
             assert_eq!(choices[1].text, "fork");
-            assert_eq!(choices[1].marked, true);
+            assert!(choices[1].marked);
         }
         _ => panic!("Expected SingleChoice"),
     }
@@ -48,6 +48,12 @@ fn test_parse_sample_quiz() {
         _ => panic!("Expected Long, got {:?}", q4.kind),
     }
     assert_eq!(q4.hints.len(), 2);
+    assert_eq!(q4.hints[0].cost, None);
+    assert_eq!(q4.hints[1].cost, Some(0.5));
+    assert_eq!(
+        q4.hints[1].text,
+        "Think about mutable vs immutable borrows."
+    );
 
     // Question 5: File with constraints
     let q5 = &quiz.questions[4];
@@ -82,3 +88,28 @@ fn test_preamble_parsing() {
     assert!(!quiz.preamble.is_empty());
     assert!(quiz.preamble[0].contains("Read all questions carefully"));
 }
+
+#[test]
+fn test_invalid_hint_cost_rejected() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    for bad in ["cost=abc", "cost=-5", "cost=NaN", "cost=inf"] {
+        let edited = content.replace(":::hint cost=0.5", &format!(":::hint {}", bad));
+        let err = termquiz::parser::parse_quiz(&edited, "test.md", "sha256:test").unwrap_err();
+        assert_eq!(
+            err,
+            format!("Invalid {} on question 4: expected a number", bad),
+            "{}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_duplicate_question_numbers_rejected() {
+    // Distinct IDs do not make a repeated number safe: answers, flags and
+    // timing are all kept per number
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let dup = content.replace("## 4. Long Answer", "## 3. Long Answer {#essay}");
+    let err = termquiz::parser::parse_quiz(&dup, "test.md", "sha256:test").unwrap_err();
+    assert_eq!(err, "Duplicate question number 3");
+}
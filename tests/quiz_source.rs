@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+
+use termquiz::persist::compute_str_hash;
+use termquiz::source::{read_quiz, resolve_source};
+
+#[test]
+fn test_root_quiz_found_among_banks() {
+    let (repo_dir, quiz_path) = resolve_source("fixtures/multi", None).unwrap();
+    assert!(repo_dir.ends_with("fixtures/multi"));
+    assert_eq!(quiz_path.file_name().unwrap(), "quiz.md");
+}
+
+#[test]
+fn test_includes_resolved_in_order() {
+    let content = read_quiz(Path::new("fixtures/multi/quiz.md")).unwrap();
+    assert!(!content.contains(":::include banks/shell.md"));
+    // Directives inside fenced code are left alone
+    assert!(content.contains(":::include not-a-directive.md"));
+
+    let quiz = termquiz::parser::parse_quiz(&content, "quiz.md", "sha256:test").unwrap();
+    let titles: Vec<&str> = quiz.questions.iter().map(|q| q.title.as_str()).collect();
+    assert_eq!(titles, vec!["Listing files", "Pipes", "Reflection"]);
+}
+
+#[test]
+fn test_hash_covers_included_files() {
+    let dir = std::env::temp_dir().join(format!("termquiz-include-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("quiz.md"),
+        "---\nstart: 2025-01-01T00:00:00Z\nend: 2030-01-01T00:00:00Z\n---\n\n# Q\n\n:::include bank.md\n",
+    )
+    .unwrap();
+    fs::write(dir.join("bank.md"), "## 1. One\n\n> short\n").unwrap();
+    let before = compute_str_hash(&read_quiz(&dir.join("quiz.md")).unwrap());
+
+    fs::write(dir.join("bank.md"), "## 1. One, edited\n\n> short\n").unwrap();
+    let after = compute_str_hash(&read_quiz(&dir.join("quiz.md")).unwrap());
+    assert_ne!(before, after);
+
+    // Cycles report the whole chain, and banks may not carry frontmatter
+    fs::write(dir.join("bank.md"), ":::include bank.md\n").unwrap();
+    let err = read_quiz(&dir.join("quiz.md")).unwrap_err();
+    assert!(err.contains("Include cycle"), "{}", err);
+    fs::write(dir.join("bank.md"), ":::include other.md\n").unwrap();
+    fs::write(dir.join("other.md"), ":::include bank.md\n").unwrap();
+    let err = read_quiz(&dir.join("quiz.md")).unwrap_err();
+    let chain = format!(
+        "{} -> {} -> {}",
+        dir.join("bank.md").display(),
+        dir.join("other.md").display(),
+        dir.join("bank.md").display()
+    );
+    assert!(err.ends_with(&chain), "{}", err);
+    fs::write(dir.join("bank.md"), "---\nstart: x\n---\n").unwrap();
+    let err = read_quiz(&dir.join("quiz.md")).unwrap_err();
+    assert!(err.contains("must not have frontmatter"), "{}", err);
+
+    // A bank opening with a horizontal rule is neither frontmatter nor a root
+    fs::write(dir.join("bank.md"), "---\n\n## 1. One\n\n> short\n\n---\n").unwrap();
+    assert!(read_quiz(&dir.join("quiz.md")).is_ok());
+    let (_, root) = resolve_source(dir.to_str().unwrap(), None).unwrap();
+    assert_eq!(root.file_name().unwrap(), "quiz.md");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_single_file_hash_unchanged() {
+    // Without includes the resolved content is the file itself, so existing
+    // saved state keeps matching
+    let raw = fs::read_to_string("fixtures/sample_quiz.md").unwrap();
+    let resolved = read_quiz(Path::new("fixtures/sample_quiz.md")).unwrap();
+    assert_eq!(raw, resolved);
+}
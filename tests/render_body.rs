@@ -0,0 +1,74 @@
+use std::fs;
+
+use ratatui::style::Color;
+use termquiz::model::BodyElement;
+use termquiz::ui::markdown::body_elements_to_lines;
+
+fn line_text(line: &ratatui::text::Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+#[test]
+fn test_code_block_language_and_line_numbers() {
+    let content = fs::read_to_string("fixtures/code_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "code_quiz.md", "sha256:test").unwrap();
+
+    let block = quiz.questions[0]
+        .body_lines
+        .iter()
+        .find_map(|e| match e {
+            BodyElement::Code(block) => Some(block),
+            _ => None,
+        })
+        .expect("Q1 should have a code block");
+    assert_eq!(block.lang.as_deref(), Some("rust"));
+    assert!(block.line_numbers);
+
+    let lines = body_elements_to_lines(&quiz.questions[0].body_lines);
+    let code: Vec<&ratatui::text::Line> = lines
+        .iter()
+        .filter(|l| line_text(l).contains(" │ "))
+        .collect();
+    assert_eq!(code.len(), 4);
+    assert!(line_text(code[2]).starts_with("  3 │ "));
+
+    // Keywords, strings and comments get distinct styles
+    let first = code[0];
+    let fn_span = first
+        .spans
+        .iter()
+        .find(|s| s.content == "fn")
+        .expect("fn span");
+    assert_eq!(fn_span.style.fg, Some(Color::Magenta));
+    let second = code[1];
+    let string_span = second
+        .spans
+        .iter()
+        .find(|s| s.content == "\"hi\"")
+        .expect("string span");
+    assert_eq!(string_span.style.fg, Some(Color::Yellow));
+    let comment_span = second
+        .spans
+        .iter()
+        .find(|s| s.content.starts_with("//"))
+        .expect("comment span");
+    assert_eq!(comment_span.style.fg, Some(Color::DarkGray));
+}
+
+#[test]
+fn test_unknown_language_falls_back_to_plain() {
+    let content = fs::read_to_string("fixtures/code_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "code_quiz.md", "sha256:test").unwrap();
+
+    let lines = body_elements_to_lines(&quiz.questions[1].body_lines);
+    let code = lines
+        .iter()
+        .find(|l| line_text(l).contains("++++"))
+        .expect("code line");
+    assert_eq!(line_text(code), "  ++++++++[>++++<-]>.");
+    assert!(code
+        .spans
+        .iter()
+        .skip(1)
+        .all(|s| s.style.fg == Some(Color::Green)));
+}
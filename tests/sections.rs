@@ -0,0 +1,225 @@
+mod common;
+
+use termquiz::clock::Clock;
+use termquiz::model::Quiz;
+use termquiz::state::{AppState, Dialog, InputMode, SidebarRow};
+
+use common::{at, working_state};
+
+/// A quiz body after the title; the frontmatter window is an hour.
+fn parse(body: &str) -> Result<Quiz, String> {
+    let content = format!(
+        "---\nstart: 2026-01-05T10:00:00Z\nend: 2026-01-05T11:00:00Z\n---\n\n# Sections\n\n{}",
+        body
+    );
+    termquiz::parser::parse_quiz(&content, "sections.md", "sha256:abc")
+}
+
+/// Two sections: a timed one with two questions, then one more question.
+fn two_sections() -> Quiz {
+    parse(
+        ":::section time=10m Part A: Closed book\n\n\
+         ## 1. First\n\nOne?\n\n- [ ] yes\n- [ ] no\n\n\
+         ## 2. Second\n\nTwo?\n\n> short\n\n\
+         :::section Part B\n\n\
+         ## 3. Third\n\nThree?\n\n> short\n",
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_parse_sections() {
+    let quiz = two_sections();
+    assert_eq!(quiz.sections.len(), 2);
+    assert_eq!(quiz.sections[0].title, "Part A: Closed book");
+    assert_eq!(quiz.sections[0].time_limit, Some(600));
+    assert_eq!(quiz.sections[1].title, "Part B");
+    assert_eq!(quiz.sections[1].time_limit, None);
+    let sections: Vec<_> = quiz.questions.iter().map(|q| q.section).collect();
+    assert_eq!(sections, [Some(0), Some(0), Some(1)]);
+
+    // Quizzes without sections are unaffected
+    let plain = parse("## 1. Only\n\nOne?\n\n> short\n").unwrap();
+    assert!(plain.sections.is_empty());
+    assert_eq!(plain.questions[0].section, None);
+}
+
+#[test]
+fn test_section_errors() {
+    let err = parse(
+        "## 1. Loose\n\nOne?\n\n> short\n\n:::section Part A\n\n## 2. Two\n\nTwo?\n\n> short\n",
+    )
+    .unwrap_err();
+    assert!(err.contains("Question 1 comes before"), "{}", err);
+
+    let err = parse(":::section Empty\n\n:::section Part A\n\n## 1. One\n\nOne?\n\n> short\n")
+        .unwrap_err();
+    assert!(err.contains("'Empty' has no questions"), "{}", err);
+
+    let err = parse(":::section time=soon Part A\n\n## 1. One\n\nOne?\n\n> short\n").unwrap_err();
+    assert!(err.contains("Invalid section time 'soon'"), "{}", err);
+
+    let err = parse(":::section time=5m\n\n## 1. One\n\nOne?\n\n> short\n").unwrap_err();
+    assert!(err.contains("needs a title"), "{}", err);
+
+    let err = parse(
+        ":::section Part A\n\n## 1. One\n\nOne?\n\n> short\n\n\
+         :::section Part A\n\n## 2. Two\n\nTwo?\n\n> short\n",
+    )
+    .unwrap_err();
+    assert!(err.contains("Duplicate section title 'Part A'"), "{}", err);
+}
+
+#[test]
+fn test_moving_on_locks_section() {
+    let mut state = working_state(two_sections(), "2026-01-05T10:00:00Z");
+    assert_eq!(state.active_section(), Some(0));
+    state.navigate_to(1);
+    assert_eq!(state.current_question, 1);
+    state.text_input = "draft".to_string();
+
+    // Crossing into the next section asks first
+    state.navigate_to(2);
+    assert_eq!(state.current_question, 1);
+    assert!(matches!(
+        state.top_dialog(),
+        Some(Dialog::ConfirmSection(2))
+    ));
+    state.pop_dialog();
+    state.advance_to(2);
+
+    assert_eq!(state.current_question, 2);
+    assert_eq!(state.active_section(), Some(1));
+    assert!(state.sections[0].locked_at.is_some());
+    assert!(state.sections[1].started_at.is_some());
+    // The draft was saved on the way out
+    assert_eq!(state.answers[&2].text.as_deref(), Some("draft"));
+
+    // Going back is allowed, but only to look
+    state.navigate_to(0);
+    assert!(!state.has_dialog());
+    assert!(state.current_is_locked());
+    assert_eq!(state.input_mode, InputMode::Navigation);
+    state.select_single_choice(0);
+    assert!(!state.answers.contains_key(&1));
+    state.navigate_to(1);
+    state.text_input = "changed".to_string();
+    state.save_current_text_input();
+    assert_eq!(state.answers[&2].text.as_deref(), Some("draft"));
+}
+
+#[test]
+fn test_section_time_runs_out() {
+    let mut state = working_state(two_sections(), "2026-01-05T10:00:00Z");
+    assert_eq!(state.check_section_time(), None);
+    assert_eq!(
+        state.sections[0].started_at.as_deref(),
+        Some("2026-01-05T10:00:00+00:00")
+    );
+    assert_eq!(state.section_remaining(), Some((0, 600)));
+
+    state.navigate_to(1);
+    state.text_input = "late".to_string();
+    state.clock = Clock::simulated(at("2026-01-05T10:10:00Z"), 0.0);
+    assert_eq!(state.section_remaining(), Some((0, 0)));
+    assert_eq!(state.check_section_time(), Some(0));
+
+    // Locked with the typed answer, and moved on to the next section
+    assert_eq!(state.answers[&2].text.as_deref(), Some("late"));
+    assert!(state.is_locked(1) && state.is_locked(2));
+    assert_eq!(state.current_question, 2);
+    assert_eq!(state.active_section(), Some(1));
+    // Part B has no limit of its own
+    assert_eq!(state.section_remaining(), None);
+    assert_eq!(state.check_section_time(), None);
+}
+
+#[test]
+fn test_sidebar_rows_group_by_section() {
+    let state = working_state(two_sections(), "2026-01-05T10:00:00Z");
+    assert_eq!(
+        state.sidebar_rows(),
+        [
+            SidebarRow::Section(0),
+            SidebarRow::Question(0),
+            SidebarRow::Question(1),
+            SidebarRow::Section(1),
+            SidebarRow::Question(2),
+        ]
+    );
+}
+
+#[test]
+fn test_section_progress_roundtrip() {
+    let mut state = working_state(two_sections(), "2026-01-05T10:00:00Z");
+    state.check_section_time();
+    state.clock = Clock::simulated(at("2026-01-05T10:04:00Z"), 0.0);
+    state.advance_to(2);
+
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(
+        parsed["sections"][0]["title"].as_str(),
+        Some("Part A: Closed book")
+    );
+    assert_eq!(
+        parsed["sections"][0]["locked_at"].as_str(),
+        Some("2026-01-05T10:04:00+00:00")
+    );
+    assert!(parsed["sections"][1].get("locked_at").is_none());
+
+    let doc = termquiz::schema::from_yaml(&yaml).unwrap();
+    let mut restored = AppState::new(two_sections(), std::env::temp_dir());
+    termquiz::persist::restore_from_doc(&mut restored, &doc);
+    assert_eq!(restored.sections, state.sections);
+    assert!(restored.is_locked(1));
+
+    // A lock survives an older snapshot that predates it
+    let mut older = doc.clone();
+    older.sections[0].progress.locked_at = None;
+    termquiz::persist::restore_from_doc(&mut restored, &older);
+    assert!(restored.is_locked(1));
+
+    // And replays from the journal alone
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_sections_journal");
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+    let mut journal = termquiz::journal::Journal::default();
+    journal.record(&tmp_dir, &doc).unwrap();
+    let entries = termquiz::journal::read_entries(&tmp_dir).unwrap();
+    assert!(entries.iter().any(|e| e.field == "sections"));
+    let mut replayed = termquiz::journal::cleared(&doc);
+    assert!(replayed.sections[0].progress.locked_at.is_none());
+    termquiz::journal::replay(&mut replayed, &entries, 0, None);
+    assert_eq!(replayed.sections, doc.sections);
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_section_lock_survives_restart_with_renamed_title() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_sections_restart");
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+    let mut state = working_state(two_sections(), "2026-01-05T10:00:00Z");
+    state.state_dir = tmp_dir.clone();
+    state.check_section_time();
+    state.advance_to(2);
+    termquiz::persist::save_state(&state).unwrap();
+
+    // The instructor renames the first section mid-exam
+    let renamed = parse(
+        ":::section time=10m Part A: No notes\n\n\
+         ## 1. First\n\nOne?\n\n- [ ] yes\n- [ ] no\n\n\
+         ## 2. Second\n\nTwo?\n\n> short\n\n\
+         :::section Part B\n\n\
+         ## 3. Third\n\nThree?\n\n> short\n",
+    )
+    .unwrap();
+    let mut restored = AppState::new(renamed, std::env::temp_dir());
+    restored.state_dir = tmp_dir.clone();
+    assert!(termquiz::persist::load_state(&mut restored).unwrap());
+    assert!(restored.is_locked(1));
+    assert!(restored.is_locked(2));
+    assert!(!restored.is_locked(3));
+    assert_eq!(restored.sections, state.sections);
+
+    let _ = std::fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use termquiz::manifest;
+use termquiz::sign;
+use termquiz::state::AppState;
+
+use common::{git, keygen};
+
+fn roster(dir: &Path, entries: &[(&str, &Path)]) -> PathBuf {
+    let lines: String = entries
+        .iter()
+        .map(|(principal, key)| {
+            let public = fs::read_to_string(key.with_extension("pub")).unwrap();
+            format!("{} {}", principal, public)
+        })
+        .collect();
+    let path = dir.join("allowed_signers");
+    fs::write(&path, lines).unwrap();
+    path
+}
+
+fn submitted_state(repo: &Path, state_dir: PathBuf) -> AppState {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc").unwrap();
+    let mut state = AppState::new(quiz, repo.to_path_buf());
+    state.state_dir = state_dir;
+    state.submitted_at = Some("2025-01-02T11:23:45-05:00".to_string());
+    state
+}
+
+#[test]
+fn test_signed_manifest_verifies_against_roster() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_sign_manifest");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let alice = keygen(&tmp_dir, "alice");
+    let mallory = keygen(&tmp_dir, "mallory");
+    let roster = roster(&tmp_dir, &[("alice", &alice)]);
+
+    sign::check_key(&alice).unwrap();
+    let state = submitted_state(&tmp_dir, tmp_dir.join("state"));
+    termquiz::submit::build_response(&state, &tmp_dir).unwrap();
+    let response = tmp_dir.join("response");
+    let file = response.join(manifest::MANIFEST_FILE);
+    let sig = sign::sign_file(&alice, &file).unwrap();
+    assert_eq!(sig, response.join(manifest::SIGNATURE_FILE));
+
+    // The signature itself is not a response file
+    assert!(manifest::verify(&response).unwrap().is_empty());
+    assert_eq!(sign::verify_file(&roster, &file, &sig).unwrap(), "alice");
+
+    // Editing the manifest breaks the signature
+    let original = fs::read_to_string(&file).unwrap();
+    fs::write(
+        &file,
+        original.replace("manifest_version: 1", "manifest_version: 2"),
+    )
+    .unwrap();
+    assert!(sign::verify_file(&roster, &file, &sig).is_err());
+    fs::write(&file, original).unwrap();
+
+    // A key outside the roster is rejected
+    let sig = sign::sign_file(&mallory, &file).unwrap();
+    let err = sign::verify_file(&roster, &file, &sig).unwrap_err();
+    assert!(err.contains("not in the roster"), "{}", err);
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_signed_submit_commit_verifies() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_sign_commit");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    let repo = tmp_dir.join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    let alice = keygen(&tmp_dir, "alice");
+    let roster = roster(&tmp_dir, &[("alice", &alice)]);
+
+    git(&["init", "-q"], &repo);
+    git(&["config", "user.name", "t"], &repo);
+    git(&["config", "user.email", "t@example.com"], &repo);
+    fs::write(repo.join("quiz.md"), "quiz").unwrap();
+    git(&["add", "."], &repo);
+    git(&["commit", "-q", "-m", "quiz"], &repo);
+
+    let state = submitted_state(&repo, tmp_dir.join("state"));
+    termquiz::submit::build_response(&state, &repo).unwrap();
+    termquiz::git::git_add(&repo, &["response/"]).unwrap();
+    termquiz::git::git_commit(&repo, "Submit", Some(&alice)).unwrap();
+
+    let commit = termquiz::git::submission_commit(&repo).unwrap();
+    assert_eq!(
+        termquiz::git::verify_commit(&repo, &commit, &roster).unwrap(),
+        "alice"
+    );
+
+    // The quiz commit before it is unsigned
+    let err = termquiz::git::verify_commit(&repo, &format!("{}~1", commit), &roster);
+    assert!(err.is_err());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_quiz_signature_checked_against_trusted_keys() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_sign_quiz");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let instructor = keygen(&tmp_dir, "instructor");
+    let student = keygen(&tmp_dir, "student");
+    let trusted = roster(&tmp_dir, &[("instructor", &instructor)]);
+
+    let quiz_path = tmp_dir.join("quiz.md");
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    fs::write(&quiz_path, &content).unwrap();
+
+    // Without a signature or trusted keys, the result is just recorded
+    assert_eq!(
+        sign::check_quiz(&quiz_path, &content, None).unwrap(),
+        "unsigned"
+    );
+    let err = sign::check_quiz(&quiz_path, &content, Some(&trusted)).unwrap_err();
+    assert!(err.contains("not signed"), "{}", err);
+
+    sign::sign_quiz(&instructor, &quiz_path, &content).unwrap();
+    assert_eq!(
+        sign::check_quiz(&quiz_path, &content, Some(&trusted)).unwrap(),
+        "verified: instructor"
+    );
+    // A signature that cannot be checked refuses to start, so deleting the
+    // trusted keys does not get around it
+    let err = sign::check_quiz(&quiz_path, &content, None).unwrap_err();
+    assert!(err.contains("no trusted keys"), "{}", err);
+
+    // Moving the end time breaks the signature
+    let extended = content.replace("end: 2026-02-12T12:00:00", "end: 2026-02-12T18:00:00");
+    assert_ne!(extended, content);
+    let err = sign::check_quiz(&quiz_path, &extended, Some(&trusted)).unwrap_err();
+    assert!(err.contains("time window"), "{}", err);
+
+    // So does re-signing with a key the student controls
+    sign::sign_quiz(&student, &quiz_path, &extended).unwrap();
+    assert!(sign::check_quiz(&quiz_path, &extended, Some(&trusted)).is_err());
+
+    // The result goes into the submission
+    let mut state = submitted_state(&tmp_dir, tmp_dir.join("state"));
+    state.quiz_signature = Some("verified: instructor".to_string());
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    assert!(
+        yaml.contains("signature: 'verified: instructor'"),
+        "{}",
+        yaml
+    );
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::Path;
+
+use termquiz::persist::{adopt_legacy_draft, state_dir_for};
+
+#[test]
+fn test_state_dir_outside_repo() {
+    let base = std::env::temp_dir().join("termquiz_test_state_env");
+    // Only test in this binary, so no other thread reads the environment
+    std::env::set_var("TERMQUIZ_STATE", &base);
+
+    let a = state_dir_for(Path::new("fixtures"));
+    let b = state_dir_for(Path::new("fixtures/multi"));
+    assert!(a.starts_with(&base));
+    assert_ne!(a, b);
+    // Same repo, however it is spelled, maps to the same directory
+    assert_eq!(a, state_dir_for(Path::new("fixtures/../fixtures")));
+
+    std::env::remove_var("TERMQUIZ_STATE");
+}
+
+#[test]
+fn test_draft_and_files_materialized_only_on_submit() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_staging");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+    let upload = tmp_dir.join("list.rs");
+    fs::write(&upload, "struct Node;").unwrap();
+
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc").unwrap();
+    let mut state = termquiz::state::AppState::new(quiz.clone(), tmp_dir.clone());
+    state.state_dir = tmp_dir.join("state");
+
+    let staged =
+        termquiz::editor::copy_file_to_state(upload.to_str().unwrap(), &state.state_dir, "q5")
+            .unwrap();
+    assert!(Path::new(&staged).starts_with(tmp_dir.join("state/files/q5")));
+    state.add_file(5, staged);
+    termquiz::persist::save_state(&state).unwrap();
+    assert!(!termquiz::git::has_response_in_worktree(&tmp_dir));
+
+    // Staged files are found again after a restart
+    let mut state2 = termquiz::state::AppState::new(quiz, tmp_dir.clone());
+    state2.state_dir = tmp_dir.join("state");
+    termquiz::persist::load_state(&mut state2).unwrap();
+    termquiz::submit::build_response(&state2, &tmp_dir).unwrap();
+    assert!(termquiz::git::has_response_in_worktree(&tmp_dir));
+    assert_eq!(
+        fs::read_to_string(tmp_dir.join("response/files/q5/list.rs")).unwrap(),
+        "struct Node;"
+    );
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_legacy_draft_moved_out_of_repo() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_legacy_draft");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(tmp_dir.join("repo/response/files/q5")).unwrap();
+    let repo = tmp_dir.join("repo");
+    let state_dir = tmp_dir.join("state");
+
+    let draft = "quiz:\n  title: \"T\"\n  source: \"q.md\"\n  submitted_at: \"unknown\"\n  duration: \"unknown\"\n\nsession:\n  current_question: 0\n  quiz_file_hash: \"sha256:abc\"\n\nquestions: []\n";
+    fs::write(repo.join("response/answers.yaml"), draft).unwrap();
+    fs::write(repo.join("response/files/q5/a.rs"), "x").unwrap();
+
+    assert!(adopt_legacy_draft(&repo, &state_dir).unwrap());
+    assert!(!repo.join("response").exists());
+    assert!(state_dir.join("answers.yaml").exists());
+    assert!(state_dir.join("files/q5/a.rs").exists());
+
+    // A real submission stays put
+    let submitted = draft.replace(
+        "submitted_at: \"unknown\"",
+        "submitted_at: \"2025-01-02T11:00:00Z\"",
+    );
+    fs::create_dir_all(repo.join("response")).unwrap();
+    fs::write(repo.join("response/answers.yaml"), submitted).unwrap();
+    fs::remove_file(state_dir.join("answers.yaml")).unwrap();
+    assert!(!adopt_legacy_draft(&repo, &state_dir).unwrap());
+    assert!(repo.join("response/answers.yaml").exists());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::fs;
+
+use termquiz::model::Answer;
+use termquiz::state::AppState;
+
+use common::git;
+
+fn status(state: &AppState) -> serde_json::Value {
+    serde_json::from_str(&termquiz::persist::status_json(state).unwrap()).unwrap()
+}
+
+#[test]
+fn test_status_json() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_status_json");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    let repo = tmp_dir.join("repo");
+    fs::create_dir_all(&repo).unwrap();
+
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc").unwrap();
+    let mut state = AppState::new(quiz, repo.clone());
+    state.state_dir = tmp_dir.join("state");
+    state.answers.insert(
+        1,
+        Answer {
+            answer_type: "single".to_string(),
+            selected: Some(vec!["b".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    state.visited.insert(1, true);
+    state.flags.insert(2, true);
+
+    let report = status(&state);
+    assert_eq!(report["quiz"]["title"], "Midterm Exam: Systems Programming");
+    assert_eq!(
+        report["repo"],
+        repo.canonicalize().unwrap().to_string_lossy().as_ref()
+    );
+    assert!(report["window"]["start"].is_string());
+    assert_eq!(report["submission"]["state"], "draft");
+    assert_eq!(report["counts"]["answered"], 1);
+    assert_eq!(report["counts"]["flagged"], 1);
+    let questions = report["questions"].as_array().unwrap();
+    assert_eq!(questions.len(), 5);
+    assert_eq!(questions[0]["status"], "answered");
+    assert_eq!(questions[1]["status"], "flagged");
+    assert_eq!(questions[2]["id"], "grep-case");
+    assert_eq!(questions[2]["status"], "unread");
+    // The fixture's window is in the past, so nothing remains
+    assert_eq!(report["window"]["state"], "closed");
+    assert!(report["remaining_seconds"].is_null());
+
+    // Submission state follows response/ through commit and push
+    let remote = tmp_dir.join("remote.git");
+    git(
+        &["init", "-q", "--bare", remote.to_str().unwrap()],
+        &tmp_dir,
+    );
+    git(&["init", "-q"], &repo);
+    fs::write(repo.join("sample_quiz.md"), &content).unwrap();
+    git(&["add", "."], &repo);
+    git(&["commit", "-q", "-m", "quiz"], &repo);
+    git(
+        &["remote", "add", "origin", remote.to_str().unwrap()],
+        &repo,
+    );
+    git(&["push", "-q", "-u", "origin", "HEAD"], &repo);
+
+    state.submitted_at = Some("2025-01-02T11:23:45-05:00".to_string());
+    termquiz::submit::build_response(&state, &repo).unwrap();
+    let report = status(&state);
+    assert_eq!(report["submission"]["state"], "written");
+    assert_eq!(
+        report["submission"]["submitted_at"],
+        "2025-01-02T11:23:45-05:00"
+    );
+
+    git(&["add", "response"], &repo);
+    git(&["commit", "-q", "-m", "submit"], &repo);
+    assert_eq!(status(&state)["submission"]["state"], "committed");
+
+    git(&["push", "-q"], &repo);
+    assert_eq!(status(&state)["submission"]["state"], "pushed");
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
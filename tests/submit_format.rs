@@ -1,5 +1,10 @@
+mod common;
+
 use std::fs;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use common::new_state;
 
 #[test]
 fn test_build_response() {
@@ -11,7 +16,7 @@ fn test_build_response() {
     let _ = fs::remove_dir_all(&tmp_dir);
     fs::create_dir_all(&tmp_dir).unwrap();
 
-    let mut state = termquiz::state::AppState::new(quiz, tmp_dir.clone());
+    let mut state = new_state(quiz, &tmp_dir);
     state.started_at = Some("2025-01-02T10:01:23-05:00".to_string());
     state.submitted_at = Some("2025-01-02T11:23:45-05:00".to_string());
 
@@ -35,10 +40,12 @@ fn test_build_response() {
         },
     );
 
-    // save_state writes response/answers.yaml
+    // save_state only writes the draft; response/ appears at submit
     termquiz::persist::save_state(&state).unwrap();
+    assert!(tmp_dir.join("state/answers.yaml").exists());
+    assert!(!tmp_dir.join("response").exists());
 
-    // Verify response directory has answers.yaml
+    termquiz::submit::build_response(&state, &tmp_dir).unwrap();
     assert!(tmp_dir.join("response/answers.yaml").exists());
 
     let yaml = fs::read_to_string(tmp_dir.join("response/answers.yaml")).unwrap();
@@ -63,7 +70,7 @@ fn test_build_response() {
 
     // Q3 short answer
     assert!(yaml.contains("type: short"));
-    assert!(yaml.contains("answer: \"-i\""));
+    assert!(yaml.contains("answer: -i"));
 
     // Unanswered questions have null
     let null_count = yaml.matches("answer: null").count();
@@ -96,7 +103,7 @@ fn test_yaml_structure() {
     let _ = fs::remove_dir_all(&tmp_dir);
     fs::create_dir_all(&tmp_dir).unwrap();
 
-    let mut state = termquiz::state::AppState::new(quiz, tmp_dir.clone());
+    let mut state = new_state(quiz, &tmp_dir);
     state.started_at = Some("2025-01-02T10:00:00-05:00".to_string());
     state.submitted_at = Some("2025-01-02T11:22:34-05:00".to_string());
     state.ack_data = Some(termquiz::model::AckData {
@@ -142,7 +149,7 @@ fn test_yaml_structure() {
     state.done_marks.insert(1, true);
     state.flags.insert(3, true);
 
-    let yaml = termquiz::submit::build_answers_yaml(&state);
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
 
     // Parse as YAML to validate structure
     let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml)
@@ -203,7 +210,7 @@ fn test_save_and_load_roundtrip() {
     fs::create_dir_all(&tmp_dir).unwrap();
 
     // Set up state with answers
-    let mut state = termquiz::state::AppState::new(quiz.clone(), tmp_dir.clone());
+    let mut state = new_state(quiz.clone(), &tmp_dir);
     state.started_at = Some("2025-01-02T10:00:00-05:00".to_string());
     state.current_question = 2;
     state.ack_data = Some(termquiz::model::AckData {
@@ -229,10 +236,10 @@ fn test_save_and_load_roundtrip() {
 
     // Save
     termquiz::persist::save_state(&state).unwrap();
-    assert!(tmp_dir.join("response/answers.yaml").exists());
+    assert!(tmp_dir.join("state/answers.yaml").exists());
 
     // Load into fresh state
-    let mut state2 = termquiz::state::AppState::new(quiz, tmp_dir.clone());
+    let mut state2 = new_state(quiz, &tmp_dir);
     let loaded = termquiz::persist::load_state(&mut state2).unwrap();
     assert!(loaded);
 
@@ -258,3 +265,365 @@ fn test_save_and_load_roundtrip() {
     // Cleanup
     let _ = fs::remove_dir_all(&tmp_dir);
 }
+
+#[test]
+fn test_hint_reveals_roundtrip() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc123").unwrap();
+
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_hint_reveals");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    // Reveal both hints on Q4 (the second one costs 0.5)
+    let mut state = new_state(quiz.clone(), &tmp_dir);
+    state.navigate_to(3);
+    assert_eq!(state.next_hint().and_then(|h| h.cost), None);
+    state.reveal_next_hint();
+    assert_eq!(state.next_hint().and_then(|h| h.cost), Some(0.5));
+    state.reveal_next_hint();
+    assert!(state.next_hint().is_none());
+    state.reveal_next_hint();
+    assert_eq!(state.hints_revealed.get(&4).copied(), Some(2));
+    assert_eq!(state.hint_penalty(4), 0.5);
+
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    let q4 = &parsed["questions"][3];
+    assert_eq!(q4["hint_used"], serde_yaml::Value::Bool(true));
+    assert_eq!(q4["hints_used"].as_u64(), Some(2));
+    assert_eq!(q4["hint_penalty"].as_f64(), Some(0.5));
+    let reveals = q4["hint_reveals"]
+        .as_sequence()
+        .expect("hint_reveals must be a list");
+    assert_eq!(reveals.len(), 2);
+    assert_eq!(reveals[1]["level"].as_u64(), Some(2));
+    assert_eq!(reveals[1]["cost"].as_f64(), Some(0.5));
+    assert!(reveals[0]["revealed_at"].as_str().is_some());
+
+    // Reveal log survives a save/load cycle
+    termquiz::persist::save_state(&state).unwrap();
+    let mut state2 = new_state(quiz, &tmp_dir);
+    termquiz::persist::load_state(&mut state2).unwrap();
+    assert_eq!(state2.hints_revealed.get(&4).copied(), Some(2));
+    assert_eq!(state2.hint_reveals.get(&4).map(|r| r.len()), Some(2));
+    assert_eq!(state2.hint_penalty(4), 0.5);
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_time_on_task_roundtrip() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc123").unwrap();
+
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_time_on_task");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    let mut state = new_state(quiz.clone(), &tmp_dir);
+    // Nothing is timed until the student is working
+    state.navigate_to(1);
+    assert!(state.timing.is_empty());
+
+    state.track_focus(true);
+    assert!(state.timing[&2].first_visited_at.is_some());
+    // Pretend Q2 had focus for 90 seconds before moving on
+    state.focus_since = Some(Instant::now() - Duration::from_secs(90));
+    // Choosing an answer stamps the edit itself
+    state.toggle_multi_choice(0);
+    assert!(state.timing[&2].last_edited_at.is_some());
+    state.navigate_to(2);
+    assert_eq!(state.time_on_question(2).as_secs(), 90);
+    // The current question counts its open interval too
+    state.focus_since = Some(Instant::now() - Duration::from_secs(30));
+    assert_eq!(state.time_on_question(3).as_secs(), 30);
+    state.track_focus(false);
+    assert_eq!(state.time_on_question(3).as_secs(), 30);
+
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    let q2 = &parsed["questions"][1];
+    assert_eq!(q2["time_spent_seconds"].as_u64(), Some(90));
+    assert!(q2["first_visited_at"].as_str().is_some());
+    assert!(q2["last_edited_at"].as_str().is_some());
+    assert_eq!(
+        parsed["questions"][2]["last_edited_at"],
+        serde_yaml::Value::Null
+    );
+    // Never-visited questions have no timing at all
+    assert!(parsed["questions"][0].get("time_spent_seconds").is_none());
+
+    termquiz::persist::save_state(&state).unwrap();
+    let mut state2 = new_state(quiz, &tmp_dir);
+    termquiz::persist::load_state(&mut state2).unwrap();
+    assert_eq!(state2.time_on_question(2).as_secs(), 90);
+    assert_eq!(
+        state2.timing[&2].last_edited_at,
+        state.timing[&2].last_edited_at
+    );
+    assert_eq!(
+        state2.timing[&3].first_visited_at,
+        state.timing[&3].first_visited_at
+    );
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_answers_follow_question_id_across_renumbering() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc123").unwrap();
+    assert_eq!(quiz.questions[2].id.as_deref(), Some("grep-case"));
+    assert_eq!(quiz.questions[2].title, "Short Answer");
+    assert_eq!(quiz.questions[0].key(), "q1");
+
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_question_id");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    let mut state = new_state(quiz.clone(), &tmp_dir);
+    state.answers.insert(
+        3,
+        termquiz::model::Answer {
+            answer_type: "short".to_string(),
+            selected: None,
+            text: Some("-i".to_string()),
+            files: None,
+        },
+    );
+    state.flags.insert(3, true);
+    termquiz::persist::save_state(&state).unwrap();
+
+    let yaml = fs::read_to_string(tmp_dir.join("state/answers.yaml")).unwrap();
+    assert!(yaml.contains("id: grep-case"));
+    assert!(yaml.contains("id: q1"));
+
+    // An erratum inserts a question before Q3, which becomes Q4
+    let edited = content
+        .replace("## 5. ", "## 6. ")
+        .replace("## 4. ", "## 5. ")
+        .replace(
+            "## 3. Short Answer {#grep-case}",
+            "## 3. Inserted\n\n> short\n\n## 4. Short Answer {#grep-case}",
+        );
+    let quiz2 = termquiz::parser::parse_quiz(&edited, "sample_quiz.md", "sha256:abc123").unwrap();
+    let mut state2 = new_state(quiz2, &tmp_dir);
+    termquiz::persist::load_state(&mut state2).unwrap();
+
+    assert!(!state2.answers.contains_key(&3));
+    let moved = state2.answers.get(&4).expect("answer should follow its ID");
+    assert_eq!(moved.text.as_deref(), Some("-i"));
+    assert!(state2.flags.get(&4).copied().unwrap_or(false));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_duplicate_question_ids_rejected() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let dup = content.replace(
+        "## 1. Multiple Choice (Single)",
+        "## 1. Multiple Choice (Single) {#grep-case}",
+    );
+    let err = termquiz::parser::parse_quiz(&dup, "sample_quiz.md", "sha256:abc123").unwrap_err();
+    assert!(err.contains("Duplicate question ID 'grep-case'"), "{}", err);
+}
+
+#[test]
+fn test_unsafe_question_ids_rejected() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    for id in ["..", ".", "a/b", "q.1", "ünï"] {
+        let bad = content.replace("{#grep-case}", &format!("{{#{}}}", id));
+        let err =
+            termquiz::parser::parse_quiz(&bad, "sample_quiz.md", "sha256:abc123").unwrap_err();
+        assert!(err.contains("Invalid question ID"), "{}: {}", id, err);
+    }
+}
+
+#[test]
+fn test_quiz_edit_migrates_saved_answers() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:before").unwrap();
+
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_migrate");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    let mut state = new_state(quiz, &tmp_dir);
+    state.answers.insert(
+        1,
+        termquiz::model::Answer {
+            answer_type: "single".to_string(),
+            selected: Some(vec!["b".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    state.answers.insert(
+        2,
+        termquiz::model::Answer {
+            answer_type: "multi".to_string(),
+            selected: Some(vec!["a".to_string(), "e".to_string()]),
+            text: None,
+            files: None,
+        },
+    );
+    state.answers.insert(
+        3,
+        termquiz::model::Answer {
+            answer_type: "short".to_string(),
+            selected: None,
+            text: Some("-i".to_string()),
+            files: None,
+        },
+    );
+    state.answers.insert(
+        4,
+        termquiz::model::Answer {
+            answer_type: "long".to_string(),
+            selected: None,
+            text: Some("Ownership.".to_string()),
+            files: None,
+        },
+    );
+    state.done_marks.insert(1, true);
+    termquiz::persist::save_state(&state).unwrap();
+
+    // Erratum: reword Q1's answer, drop Q2's last choice, make Q4 a file question
+    let edited = content
+        .replace("- [x] fork", "- [x] fork()")
+        .replace("- [ ] long\n", "")
+        .replace(
+            "Explain how the borrow checker prevents data races at compile time.\n\n> long",
+            "Upload your notes on the borrow checker.\n\n> file",
+        );
+    let quiz2 = termquiz::parser::parse_quiz(&edited, "sample_quiz.md", "sha256:after").unwrap();
+    let mut state2 = new_state(quiz2, &tmp_dir);
+    assert!(termquiz::persist::load_state(&mut state2).unwrap());
+
+    // Unchanged answer carries over
+    assert_eq!(state2.answers.get(&3).unwrap().text.as_deref(), Some("-i"));
+    // Reworded choice keeps the selection but is flagged and no longer done
+    assert_eq!(
+        state2.answers.get(&1).unwrap().selected.as_ref().unwrap(),
+        &vec!["b".to_string()]
+    );
+    assert!(state2.flags.get(&1).copied().unwrap_or(false));
+    assert!(!state2.done_marks.contains_key(&1));
+    // Removed choice is deselected
+    assert_eq!(
+        state2.answers.get(&2).unwrap().selected.as_ref().unwrap(),
+        &vec!["a".to_string()]
+    );
+    // Type change discards the answer
+    assert!(!state2.answers.contains_key(&4));
+
+    let notes = state2.migration_notes.join("\n");
+    assert!(
+        notes.contains("Q1 Multiple Choice (Single): wording of choice b changed"),
+        "{}",
+        notes
+    );
+    assert!(
+        notes.contains("Q2 Multiple Choice (Multi): choice e no longer exists"),
+        "{}",
+        notes
+    );
+    assert!(
+        notes.contains("Q4 Long Answer: question type changed"),
+        "{}",
+        notes
+    );
+    assert_eq!(state2.migration_notes.len(), 3);
+
+    // Same quiz again: nothing to migrate
+    let mut state3 = new_state(state.quiz.clone(), &tmp_dir);
+    termquiz::persist::load_state(&mut state3).unwrap();
+    assert!(state3.migration_notes.is_empty());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_long_answer_whitespace_roundtrip() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc123").unwrap();
+
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_long_roundtrip");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    let texts = [
+        "    fn main() {}\n\tindented with a tab",
+        "trailing blank lines\n\n\n",
+        "\n\nleading blank lines",
+        "quotes \" and 'apostrophes' and: colons # hash",
+        "unicode ∑ and \\backslash\\",
+    ];
+    for text in texts {
+        let mut state = new_state(quiz.clone(), &tmp_dir);
+        state.answers.insert(
+            4,
+            termquiz::model::Answer {
+                answer_type: "long".to_string(),
+                selected: None,
+                text: Some(text.to_string()),
+                files: None,
+            },
+        );
+        termquiz::persist::save_state(&state).unwrap();
+
+        let mut state2 = new_state(quiz.clone(), &tmp_dir);
+        termquiz::persist::load_state(&mut state2).unwrap();
+        assert_eq!(state2.answers.get(&4).unwrap().text.as_deref(), Some(text));
+    }
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_schema_version_and_legacy_upgrade() {
+    let content = fs::read_to_string("fixtures/sample_quiz.md").expect("Cannot read fixture");
+    let quiz = termquiz::parser::parse_quiz(&content, "sample_quiz.md", "sha256:abc123").unwrap();
+    let state = termquiz::state::AppState::new(quiz, std::env::temp_dir());
+    let yaml = termquiz::submit::build_answers_yaml(&state).unwrap();
+    let parsed: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(
+        parsed["schema_version"].as_u64(),
+        Some(termquiz::schema::SCHEMA_VERSION as u64)
+    );
+
+    // A file from before versioning: hand-written, `|` block, hint_used only
+    let legacy = r#"quiz:
+  title: "Midterm"
+  source: "sample_quiz.md"
+  submitted_at: "unknown"
+  duration: "unknown"
+
+session:
+  current_question: 3
+  quiz_file_hash: "sha256:abc123"
+
+questions:
+  - number: 4
+    title: "Long Answer"
+    type: long
+    hint_used: true
+    answer: |
+      First line
+      second line
+"#;
+    let doc = termquiz::schema::from_yaml(legacy).unwrap();
+    assert_eq!(doc.schema_version, termquiz::schema::SCHEMA_VERSION);
+    let q4 = &doc.questions[0];
+    assert_eq!(q4.hints_used, 1);
+    assert_eq!(
+        q4.answer.as_ref().and_then(|a| a.as_text()),
+        Some("First line\nsecond line")
+    );
+
+    let future = "schema_version: 99\nquiz: {}\n";
+    let err = termquiz::schema::from_yaml(future).unwrap_err();
+    assert!(err.contains("newer than this termquiz supports"), "{}", err);
+}
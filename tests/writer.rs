@@ -0,0 +1,75 @@
+mod common;
+
+use std::fs;
+use std::time::Duration;
+
+use termquiz::journal;
+use termquiz::writer::{self, WriterEvent};
+
+use common::{sample_state, short_answer};
+
+#[test]
+fn test_writer_coalesces_updates() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_writer_debounce");
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    let mut state = sample_state(&tmp_dir);
+    let writer = writer::spawn_writer(&state);
+
+    // A burst of keystrokes within the debounce lands as one entry
+    for text in ["g", "gr", "gre", "grep -i"] {
+        state.answers.insert(3, short_answer(text));
+        writer.update(&state);
+    }
+    assert!(!journal::journal_path(&state.state_dir).exists());
+
+    std::thread::sleep(writer::DEBOUNCE + Duration::from_millis(300));
+    let entries = journal::read_entries(&state.state_dir).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].new, serde_json::json!("grep -i"));
+    // The snapshot is only rewritten on sync or after many entries
+    assert!(!state.state_dir.join("answers.yaml").exists());
+
+    state.flags.insert(1, true);
+    writer.sync(&mut state).unwrap();
+    assert_eq!(state.journal.seq, 2);
+    let yaml = fs::read_to_string(state.state_dir.join("answers.yaml")).unwrap();
+    assert!(yaml.contains("journal_seq: 2"));
+
+    let mut restored = sample_state(&tmp_dir);
+    assert!(termquiz::persist::load_state(&mut restored).unwrap());
+    assert_eq!(restored.answers[&3].text.as_deref(), Some("grep -i"));
+    assert_eq!(restored.flags.get(&1), Some(&true));
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}
+
+#[test]
+fn test_writer_reports_failures() {
+    let tmp_dir = std::env::temp_dir().join("termquiz_test_writer_failure");
+    let _ = fs::remove_dir_all(&tmp_dir);
+    fs::create_dir_all(&tmp_dir).unwrap();
+
+    // A regular file where the state directory should be
+    let mut state = sample_state(&tmp_dir);
+    fs::write(&state.state_dir, "not a directory").unwrap();
+    let writer = writer::spawn_writer(&state);
+
+    state.answers.insert(3, short_answer("-i"));
+    writer.flush(&state);
+    let event = writer.events.recv_timeout(Duration::from_secs(2)).unwrap();
+    assert!(matches!(event, WriterEvent::Failed(_)));
+    assert!(writer.sync(&mut state).is_err());
+
+    // Once the directory is usable again the retry succeeds
+    fs::remove_file(&state.state_dir).unwrap();
+    writer.sync(&mut state).unwrap();
+    let recovered = writer
+        .events
+        .try_iter()
+        .any(|e| matches!(e, WriterEvent::Recovered));
+    assert!(recovered);
+    assert!(state.state_dir.join("answers.yaml").exists());
+
+    let _ = fs::remove_dir_all(&tmp_dir);
+}